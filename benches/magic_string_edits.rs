@@ -0,0 +1,54 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use string_wizard::MagicString;
+
+fn get_threejs_source() -> String {
+  let files = glob::glob("fixtures/threejs_src/**/*.js").unwrap();
+  let mut files = files.into_iter().map(|p| p.unwrap().canonicalize().unwrap()).collect::<Vec<_>>();
+  files.sort();
+  files.iter().map(|p| std::fs::read_to_string(p).unwrap()).collect::<Vec<_>>().concat()
+}
+
+/// Char boundaries of `source` in a deterministic pseudo-random order.
+fn random_positions(source: &str, count: usize) -> Vec<usize> {
+  // xorshift64, good enough to scatter the edits
+  let mut state = 0x2545_f491_4f6c_dd1d_u64;
+  let mut positions = Vec::with_capacity(count);
+  while positions.len() < count {
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    let position = (state % source.len() as u64) as usize;
+    if source.is_char_boundary(position) {
+      positions.push(position);
+    }
+  }
+  positions
+}
+
+fn edit(source: &str, positions: &[usize]) -> usize {
+  let mut s = MagicString::new(source);
+  for &position in positions {
+    s.append_left(position, "/**/");
+    if let Some(char) = source[position..].chars().next() {
+      s.remove(position, position + char.len_utf8());
+    }
+  }
+  s.len()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+  let source = get_threejs_source();
+  let random_positions = random_positions(&source, 10_000);
+  let mut sorted_positions = random_positions.clone();
+  sorted_positions.sort_unstable();
+
+  c.bench_function("MagicString#edits in random order", |b| {
+    b.iter(|| black_box(edit(&source, &random_positions)))
+  });
+  c.bench_function("MagicString#edits in source order", |b| {
+    b.iter(|| black_box(edit(&source, &sorted_positions)))
+  });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
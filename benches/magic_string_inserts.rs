@@ -0,0 +1,37 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use string_wizard::MagicString;
+
+fn get_threejs_sources() -> Vec<String> {
+  let files = glob::glob("fixtures/threejs_src/**/*.js").unwrap();
+  let mut files = files.into_iter().map(|p| p.unwrap().canonicalize().unwrap()).collect::<Vec<_>>();
+  files.sort();
+  files.iter().map(|p| std::fs::read_to_string(p).unwrap()).collect()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+  let sources = get_threejs_sources();
+
+  c.bench_function("MagicString#indent", |b| {
+    b.iter(|| {
+      for source in &sources {
+        let mut s = MagicString::new(source.as_str());
+        s.indent();
+        black_box(s.len());
+      }
+    })
+  });
+  c.bench_function("MagicString#append_left at every line end", |b| {
+    b.iter(|| {
+      for source in &sources {
+        let mut s = MagicString::new(source.as_str());
+        for (idx, _) in source.match_indices('\n') {
+          s.append_left(idx, " //").append_left(idx, ";");
+        }
+        black_box(s.len());
+      }
+    })
+  });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,6 +1,8 @@
-use std::collections::VecDeque;
-
-use crate::{span::Span, CowStr};
+use crate::{
+  fragment::{FragmentArena, FragmentIdx, FragmentList},
+  span::Span,
+  CowStr,
+};
 
 index_vec::define_index_type! {
     pub struct ChunkIdx = u32;
@@ -19,24 +21,29 @@ impl Default for EditOptions {
   }
 }
 
+/// The content of a chunk is stored in a [FragmentArena] shared by all chunks of a
+/// [crate::MagicString], so a chunk only holds handles into it.
 #[derive(Debug, Default, Clone)]
-pub struct Chunk<'str> {
-  pub intro: VecDeque<CowStr<'str>>,
-  pub outro: VecDeque<CowStr<'str>>,
+pub struct Chunk {
+  pub intro: FragmentList,
+  pub outro: FragmentList,
   pub span: Span,
-  pub edited_content: Option<CowStr<'str>>,
+  pub edited_content: Option<FragmentIdx>,
   pub next: Option<ChunkIdx>,
   pub prev: Option<ChunkIdx>,
   pub keep_in_mappings: bool,
+  /// Whether the chunk is a part of a replacement whose content is stored on an earlier chunk in
+  /// the original order, as opposed to a removal. Such a chunk is edited to be empty.
+  pub continues_edit: bool,
 }
 
-impl<'s> Chunk<'s> {
+impl Chunk {
   pub fn new(span: Span) -> Self {
     Self { span, ..Default::default() }
   }
 }
 
-impl<'str> Chunk<'str> {
+impl Chunk {
   pub fn start(&self) -> usize {
     self.span.start()
   }
@@ -49,65 +56,108 @@ impl<'str> Chunk<'str> {
     self.start() < text_index && text_index < self.end()
   }
 
-  pub fn append_outro(&mut self, content: CowStr<'str>) {
-    self.outro.push_back(content)
+  pub fn append_outro<'str>(&mut self, arena: &mut FragmentArena<'str>, content: CowStr<'str>) {
+    arena.push_back(&mut self.outro, content)
   }
 
-  pub fn append_intro(&mut self, content: CowStr<'str>) {
-    self.intro.push_back(content)
+  pub fn append_intro<'str>(&mut self, arena: &mut FragmentArena<'str>, content: CowStr<'str>) {
+    arena.push_back(&mut self.intro, content)
   }
 
-  pub fn prepend_outro(&mut self, content: CowStr<'str>) {
-    self.outro.push_front(content)
+  pub fn prepend_outro<'str>(&mut self, arena: &mut FragmentArena<'str>, content: CowStr<'str>) {
+    arena.push_front(&mut self.outro, content)
   }
 
-  pub fn prepend_intro(&mut self, content: CowStr<'str>) {
-    self.intro.push_front(content)
+  pub fn prepend_intro<'str>(&mut self, arena: &mut FragmentArena<'str>, content: CowStr<'str>) {
+    arena.push_front(&mut self.intro, content)
   }
 
-  pub fn split<'a>(&'a mut self, text_index: usize) -> Chunk<'str> {
+  /// Split the chunk at `text_index` and return the second half. The `intro` stays with the first
+  /// half and the `outro` moves to the second half.
+  ///
+  /// Splitting an edited chunk keeps the edited content on the first half and makes the second half
+  /// an empty edit that continues it. Both halves of a removed chunk are removed.
+  pub fn split(&mut self, arena: &mut FragmentArena, text_index: usize) -> Chunk {
     if !(text_index > self.start() && text_index < self.end()) {
       panic!("Cannot split chunk at {text_index} between {:?}", self.span);
     }
-    if self.edited_content.is_some() {
-      panic!("Cannot split a chunk that has already been edited")
-    }
     let first_half_slice = Span(self.start(), text_index);
     let second_half_slice = Span(text_index, self.end());
     let mut new_chunk = Chunk::new(second_half_slice);
     if self.is_edited() {
-      new_chunk
-        .edit("".into(), EditOptions { store_name: self.keep_in_mappings, overwrite: false });
+      new_chunk.edit(
+        arena,
+        "".into(),
+        // The name belongs to the first half, which holds the content
+        EditOptions { store_name: false, overwrite: false },
+      );
+      new_chunk.continues_edit = !self.is_removed(arena);
     }
     std::mem::swap(&mut new_chunk.outro, &mut self.outro);
     self.span = first_half_slice;
     new_chunk
   }
 
-  pub fn fragments(
-    &'str self,
-    original_source: &'str CowStr<'str>,
-  ) -> impl Iterator<Item = &'str str> {
-    let intro_iter = self.intro.iter().map(|frag| frag.as_ref());
+  pub fn fragments<'a>(
+    &self,
+    arena: &'a FragmentArena,
+    original_source: &'a str,
+  ) -> impl Iterator<Item = &'a str> {
     let source_frag = self
-      .edited_content
-      .as_ref()
+      .edited_content(arena)
       .map(|s| s.as_ref())
-      .unwrap_or_else(|| self.span.text(&original_source));
-    let outro_iter = self.outro.iter().map(|frag| frag.as_ref());
-    intro_iter.chain(Some(source_frag)).chain(outro_iter)
+      .unwrap_or_else(|| self.span.text(original_source));
+    arena.iter(self.intro).chain(Some(source_frag)).chain(arena.iter(self.outro))
+  }
+
+  /// The content the chunk was edited to, if any.
+  pub fn edited_content<'a, 'str>(
+    &self,
+    arena: &'a FragmentArena<'str>,
+  ) -> Option<&'a CowStr<'str>> {
+    self.edited_content.map(|idx| arena.get(idx))
   }
 
-  pub fn edit(&mut self, content: CowStr<'str>, opts: EditOptions) {
+  pub fn edit<'str>(
+    &mut self,
+    arena: &mut FragmentArena<'str>,
+    content: CowStr<'str>,
+    opts: EditOptions,
+  ) {
     if opts.overwrite {
       self.intro.clear();
       self.outro.clear();
     }
     self.keep_in_mappings = opts.store_name;
-    self.edited_content = Some(content);
+    self.continues_edit = false;
+    match self.edited_content {
+      // Reuse the slot, since repeated edits of the same chunk are common
+      Some(idx) => *arena.get_mut(idx) = content,
+      None => self.edited_content = Some(arena.alloc(content)),
+    }
+  }
+
+  /// Drop the edited content, so the chunk renders its original content again.
+  pub fn reset_content(&mut self) {
+    self.edited_content = None;
+    self.keep_in_mappings = false;
+    self.continues_edit = false;
   }
 
   pub fn is_edited(&self) -> bool {
     self.edited_content.is_some()
   }
+
+  /// Whether the chunk is edited to be empty by a removal, rather than being a part of a
+  /// replacement.
+  pub fn is_removed(&self, arena: &FragmentArena) -> bool {
+    !self.continues_edit && self.edited_content(arena).is_some_and(|content| content.is_empty())
+  }
+}
+
+#[test]
+fn chunks_only_hold_handles_to_their_content() {
+  // The size doesn't depend on how much content is inserted into the chunk. It's 80 bytes on 64-bit
+  // targets and less on smaller ones.
+  assert!(std::mem::size_of::<Chunk>() <= 80);
 }
@@ -0,0 +1,52 @@
+use std::fmt;
+
+/// Errors reported by the fallible `try_*` editing methods of [crate::MagicString].
+///
+/// All indices are byte offsets into the original source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MagicStringError {
+  /// `index` is greater than the length `len` of the original source.
+  OutOfBounds { index: usize, len: usize },
+  /// `index` doesn't lie on a char boundary of the original source.
+  NotCharBoundary { index: usize },
+  /// `start` is greater than `end`.
+  InvalidRange { start: usize, end: usize },
+  /// Tried to overwrite the zero-length range at `index`.
+  ZeroLengthRange { index: usize },
+  /// `index` lies inside the range `edited_start..edited_end`, which has already been edited.
+  OverlappingEdit { index: usize, edited_start: usize, edited_end: usize },
+  /// Tried to move `start..end` to `to`, which lies inside the moved range itself.
+  MoveIntoSelf { start: usize, end: usize, to: usize },
+  /// `end` of the range `start..end` is not rendered after `start` because of relocations.
+  RelocatedRange { start: usize, end: usize },
+}
+
+impl fmt::Display for MagicStringError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::OutOfBounds { index, len } => {
+        write!(f, "Index {index} is out of bounds of the source with length {len}")
+      }
+      Self::NotCharBoundary { index } => write!(f, "Index {index} is not a char boundary"),
+      Self::InvalidRange { start, end } => {
+        write!(f, "Invalid range {start}..{end}, start must not be greater than end")
+      }
+      Self::ZeroLengthRange { index } => write!(
+        f,
+        "Cannot overwrite a zero-length range at {index} – use append_left or prepend_right instead"
+      ),
+      Self::OverlappingEdit { index, edited_start, edited_end } => write!(
+        f,
+        "Cannot split a chunk that has already been edited: {index} is inside the edited range {edited_start}..{edited_end}"
+      ),
+      Self::MoveIntoSelf { start, end, to } => {
+        write!(f, "Cannot relocate a selection inside itself: {to} is inside {start}..{end}")
+      }
+      Self::RelocatedRange { start, end } => {
+        write!(f, "Cannot use {start}..{end}, its end has been relocated before its start")
+      }
+    }
+  }
+}
+
+impl std::error::Error for MagicStringError {}
@@ -0,0 +1,135 @@
+use index_vec::IndexVec;
+
+use crate::CowStr;
+
+index_vec::define_index_type! {
+    pub struct FragmentIdx = u32;
+}
+
+#[derive(Debug, Clone)]
+struct Fragment<'str> {
+  content: CowStr<'str>,
+  prev: Option<FragmentIdx>,
+  next: Option<FragmentIdx>,
+}
+
+/// A list of fragments living in a [FragmentArena]. It only holds the handles of its ends, so
+/// chunks stay small no matter how much content is inserted into them.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FragmentList {
+  head: Option<FragmentIdx>,
+  tail: Option<FragmentIdx>,
+}
+
+impl FragmentList {
+  pub fn is_empty(&self) -> bool {
+    self.head.is_none()
+  }
+
+  /// Detach all fragments. They stay in the arena until it's dropped.
+  pub fn clear(&mut self) {
+    *self = Self::default();
+  }
+}
+
+/// Storage of the inserted and edited content of a [crate::MagicString].
+///
+/// Fragments are never freed on their own, since removing all of them at once is the common case.
+#[derive(Debug, Default, Clone)]
+pub struct FragmentArena<'str> {
+  fragments: IndexVec<FragmentIdx, Fragment<'str>>,
+}
+
+impl<'str> FragmentArena<'str> {
+  /// Store `content` as a fragment that isn't part of any [FragmentList].
+  pub fn alloc(&mut self, content: CowStr<'str>) -> FragmentIdx {
+    self.fragments.push(Fragment { content, prev: None, next: None })
+  }
+
+  pub fn get(&self, idx: FragmentIdx) -> &CowStr<'str> {
+    &self.fragments[idx].content
+  }
+
+  pub fn get_mut(&mut self, idx: FragmentIdx) -> &mut CowStr<'str> {
+    &mut self.fragments[idx].content
+  }
+
+  pub fn push_back(&mut self, list: &mut FragmentList, content: CowStr<'str>) {
+    let idx = self.fragments.push(Fragment { content, prev: list.tail, next: None });
+    match list.tail {
+      Some(tail) => self.fragments[tail].next = Some(idx),
+      None => list.head = Some(idx),
+    }
+    list.tail = Some(idx);
+  }
+
+  pub fn push_front(&mut self, list: &mut FragmentList, content: CowStr<'str>) {
+    let idx = self.fragments.push(Fragment { content, prev: None, next: list.head });
+    match list.head {
+      Some(head) => self.fragments[head].prev = Some(idx),
+      None => list.tail = Some(idx),
+    }
+    list.head = Some(idx);
+  }
+
+  /// Move the fragments of `list` into `to`, leaving empty fragments behind. Returns the list in
+  /// `to`.
+  pub fn move_to(&mut self, list: FragmentList, to: &mut FragmentArena<'str>) -> FragmentList {
+    let mut moved = FragmentList::default();
+    let mut next = list.head;
+    while let Some(idx) = next {
+      let fragment = &mut self.fragments[idx];
+      to.push_back(&mut moved, std::mem::take(&mut fragment.content));
+      next = if Some(idx) == list.tail { None } else { fragment.next };
+    }
+    moved
+  }
+
+  /// Like [FragmentArena::move_to], for a fragment allocated with [FragmentArena::alloc].
+  pub fn move_one_to(&mut self, idx: FragmentIdx, to: &mut FragmentArena<'str>) -> FragmentIdx {
+    to.alloc(std::mem::take(self.get_mut(idx)))
+  }
+
+  /// Iterate over the indices of the fragments of `list`, use [FragmentArena::iter] to read them.
+  pub fn indices(&self, list: FragmentList) -> FragmentIndices<'_, 'str> {
+    FragmentIndices { arena: self, front: list.head, back: list.tail }
+  }
+
+  pub fn iter(&self, list: FragmentList) -> impl DoubleEndedIterator<Item = &str> {
+    self.indices(list).map(|idx| self.get(idx).as_ref())
+  }
+}
+
+pub struct FragmentIndices<'a, 'str> {
+  arena: &'a FragmentArena<'str>,
+  front: Option<FragmentIdx>,
+  back: Option<FragmentIdx>,
+}
+
+impl Iterator for FragmentIndices<'_, '_> {
+  type Item = FragmentIdx;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let idx = self.front?;
+    if self.front == self.back {
+      self.front = None;
+      self.back = None;
+    } else {
+      self.front = self.arena.fragments[idx].next;
+    }
+    Some(idx)
+  }
+}
+
+impl DoubleEndedIterator for FragmentIndices<'_, '_> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let idx = self.back?;
+    if self.front == self.back {
+      self.front = None;
+      self.back = None;
+    } else {
+      self.back = self.arena.fragments[idx].prev;
+    }
+    Some(idx)
+  }
+}
@@ -1,4 +1,8 @@
-use crate::{CowStr, MagicString};
+use std::{fmt, hash::Hasher, io};
+
+#[cfg(feature = "source_map")]
+use crate::{source_map::sourcemap_builder::SourcemapBuilder, SourceMapOptions};
+use crate::{CowStr, HashPlaceholders, MagicString};
 
 pub struct JoinerOptions {
   pub separator: Option<String>,
@@ -34,6 +38,14 @@ impl<'s> Joiner<'s> {
     self.fragments().map(|s| s.len()).sum()
   }
 
+  // `Option::is_none_or` would need Rust 1.82
+  #[allow(clippy::unnecessary_map_or)]
+  pub fn is_empty(&self) -> bool {
+    let separator_is_empty =
+      self.sources.len() < 2 || self.separator.as_deref().map_or(true, str::is_empty);
+    separator_is_empty && self.sources.iter().all(MagicString::is_empty)
+  }
+
   pub fn join(&self) -> String {
     let mut ret = String::with_capacity(self.len());
     self.fragments().for_each(|frag| {
@@ -42,9 +54,55 @@ impl<'s> Joiner<'s> {
     ret
   }
 
+  /// Write the joined content to `w` piece by piece, without rendering it into a [String] first.
+  pub fn write_to(&self, w: &mut impl io::Write) -> io::Result<()> {
+    self.fragments().try_for_each(|frag| w.write_all(frag.as_bytes()))
+  }
+
+  /// Like [Joiner::write_to], but for a [fmt::Write].
+  pub fn write_fmt_to(&self, w: &mut impl fmt::Write) -> fmt::Result {
+    self.fragments().try_for_each(|frag| w.write_str(frag))
+  }
+
+  /// Feed the joined content into `state` piece by piece, see [MagicString::hash_into].
+  pub fn hash_into<H: Hasher>(&self, state: &mut H) {
+    self.fragments().for_each(|frag| state.write(frag.as_bytes()))
+  }
+
+  /// Feed the joined content into `state`, see [MagicString::hash_normalized_into].
+  pub fn hash_normalized_into<H: Hasher>(&self, placeholders: &HashPlaceholders, state: &mut H) {
+    placeholders.hash_normalized(self.fragments(), state)
+  }
+
+  /// Generate a sourcemap for the output of [Joiner::join].
+  ///
+  /// Each appended [MagicString] is registered as a source by its `filename`. Sources without
+  /// a `filename`, such as the ones added by [Joiner::append_raw], produce no mappings.
+  #[cfg(feature = "source_map")]
+  pub fn source_map(&self, opts: SourceMapOptions) -> oxc_sourcemap::SourceMap {
+    let mut source_builder = SourcemapBuilder::new(opts.hires);
+
+    for (idx, source) in self.sources.iter().enumerate() {
+      if idx > 0 {
+        if let Some(separator) = &self.separator {
+          source_builder.advance(separator);
+        }
+      }
+      match &source.filename {
+        Some(filename) => {
+          source_builder.add_source_and_content(filename, &source.source);
+          source.add_to_sourcemap_builder(&mut source_builder);
+        }
+        None => source.fragments().for_each(|frag| source_builder.advance(frag)),
+      }
+    }
+
+    source_builder.into_source_map(&opts)
+  }
+
   // --- private
 
-  fn fragments(&'s self) -> impl Iterator<Item = &'s str> {
+  fn fragments(&self) -> impl Iterator<Item = &str> {
     let mut iter =
       self.sources.iter().flat_map(|c| self.separator.as_deref().into_iter().chain(c.fragments()));
     // Drop the first separator
@@ -1,6 +1,9 @@
 mod chunk;
+mod error;
+mod fragment;
 mod joiner;
 mod magic_string;
+mod placeholder;
 #[cfg(feature = "source_map")]
 mod source_map;
 mod span;
@@ -11,9 +14,21 @@ type CowStr<'a> = Cow<'a, str>;
 use std::borrow::Cow;
 
 pub use crate::{
+  error::MagicStringError,
   joiner::{Joiner, JoinerOptions},
-  magic_string::{indent::IndentOptions, update::UpdateOptions, MagicString, MagicStringOptions},
+  magic_string::{
+    edits::Edit,
+    indent::IndentOptions,
+    marker::{Bias, Marker},
+    position::{LineColumn, OriginalPosition},
+    reset::ResetOptions,
+    segment::{Segment, SegmentKind},
+    update::{OverlapPolicy, UpdateOptions},
+    MagicString, MagicStringOptions,
+  },
+  placeholder::HashPlaceholders,
+  span::Span,
 };
 
 #[cfg(feature = "source_map")]
-pub use crate::magic_string::source_map::SourceMapOptions;
+pub use crate::magic_string::source_map::{Hires, SourceMapOptions};
@@ -1,4 +1,4 @@
-use crate::CowStr;
+use crate::{CowStr, MagicStringError};
 
 use super::MagicString;
 
@@ -17,13 +17,23 @@ impl<'text> MagicString<'text> {
   /// assert_eq!(s.to_string(), "01ab234")
   ///```
   pub fn append_left(&mut self, text_index: usize, content: impl Into<CowStr<'text>>) -> &mut Self {
-    match self.by_end_mut(text_index) {
-      Some(chunk) => {
-        chunk.append_outro(content.into());
+    self.try_append_left(text_index, content).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::append_left].
+  pub fn try_append_left(
+    &mut self,
+    text_index: usize,
+    content: impl Into<CowStr<'text>>,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.compact_if_needed();
+    match self.by_end_mut(text_index)? {
+      Some((chunk, arena)) => {
+        chunk.append_outro(arena, content.into());
       }
       None => self.append_intro(content.into()),
     }
-    self
+    Ok(self)
   }
 
   /// # Example
@@ -41,12 +51,22 @@ impl<'text> MagicString<'text> {
     text_index: usize,
     content: impl Into<CowStr<'text>>,
   ) -> &mut Self {
-    match self.by_start_mut(text_index) {
-      Some(chunk) => {
-        chunk.append_intro(content.into());
+    self.try_append_right(text_index, content).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::append_right].
+  pub fn try_append_right(
+    &mut self,
+    text_index: usize,
+    content: impl Into<CowStr<'text>>,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.compact_if_needed();
+    match self.by_start_mut(text_index)? {
+      Some((chunk, arena)) => {
+        chunk.append_intro(arena, content.into());
       }
       None => self.append_outro(content.into()),
     }
-    self
+    Ok(self)
   }
 }
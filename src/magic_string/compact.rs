@@ -0,0 +1,108 @@
+use crate::{
+  chunk::{Chunk, ChunkIdx},
+  fragment::FragmentArena,
+  span::Span,
+  type_aliases::IndexChunks,
+  MagicString,
+};
+
+impl<'text> MagicString<'text> {
+  /// Merges adjacent chunks that have no edits and no content inserted between them, and drops
+  /// the storage of everything that is no longer rendered, such as content cleared by
+  /// [MagicString::reset] or overwritten by [MagicString::update].
+  ///
+  /// The output stays the same, but later edits and sourcemap generation have fewer chunks to go
+  /// through. Sourcemaps can get smaller too, since merged chunks no longer start new mappings.
+  /// See [crate::MagicStringOptions::compact_threshold] to run it automatically.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("abcdefghijkl");
+  /// s.update(3, 6, "DEF").append_left(9, "!");
+  /// s.reset(0, 12).compact();
+  /// assert_eq!(s.to_string(), "abcdefghijkl");
+  /// ```
+  pub fn compact(&mut self) -> &mut Self {
+    let mut chunks = IndexChunks::with_capacity(self.chunks.len());
+    let mut frag_arena = FragmentArena::default();
+    self.intro = self.frag_arena.move_to(self.intro, &mut frag_arena);
+
+    let mut next_chunk_idx = Some(self.first_chunk_idx);
+    let mut last_idx = None;
+    while let Some(chunk_idx) = next_chunk_idx {
+      let chunk = std::mem::take(&mut self.chunks[chunk_idx]);
+      next_chunk_idx = chunk.next;
+      if let Some(last_idx) = last_idx {
+        let last: &mut Chunk = &mut chunks[last_idx];
+        let can_merge = last.end() == chunk.start()
+          && !last.is_edited()
+          && !chunk.is_edited()
+          && last.outro.is_empty()
+          && chunk.intro.is_empty();
+        if can_merge {
+          last.span = Span(last.start(), chunk.end());
+          last.outro = self.frag_arena.move_to(chunk.outro, &mut frag_arena);
+          continue;
+        }
+      }
+
+      let compacted = Chunk {
+        intro: self.frag_arena.move_to(chunk.intro, &mut frag_arena),
+        outro: self.frag_arena.move_to(chunk.outro, &mut frag_arena),
+        edited_content: chunk
+          .edited_content
+          .map(|idx| self.frag_arena.move_one_to(idx, &mut frag_arena)),
+        prev: last_idx,
+        next: None,
+        ..chunk
+      };
+      let compacted_idx = chunks.push(compacted);
+      if let Some(last_idx) = last_idx {
+        chunks[last_idx].next = Some(compacted_idx);
+      }
+      last_idx = Some(compacted_idx);
+    }
+
+    self.outro = self.frag_arena.move_to(self.outro, &mut frag_arena);
+    self.frag_arena = frag_arena;
+
+    self.chunk_by_start.clear();
+    self.chunk_by_end.clear();
+    for (idx, chunk) in chunks.iter_enumerated() {
+      self.chunk_by_start.insert(chunk.start(), idx);
+      self.chunk_by_end.insert(chunk.end(), idx);
+    }
+    self.first_chunk_idx = ChunkIdx::new(0);
+    // There is always at least one chunk
+    self.last_chunk_idx = last_idx.unwrap();
+    self.chunks = chunks;
+    self
+  }
+
+  // --- private
+
+  /// Run [MagicString::compact] if the number of chunks reached the threshold. It must only be
+  /// called when no chunk index is held, since compacting renumbers the chunks.
+  pub(super) fn compact_if_needed(&mut self) {
+    if self.next_compaction_at.is_some_and(|at| self.chunks.len() >= at) {
+      self.compact();
+      // Wait for the number of chunks to double, so compacting stays amortized O(1) per edit
+      self.next_compaction_at = self.next_compaction_at.map(|at| at.max(self.chunks.len() * 2));
+    }
+  }
+}
+
+#[test]
+fn merges_untouched_neighbors() {
+  let mut s = MagicString::new("abcdefghijkl");
+  s.update(3, 6, "DEF").append_left(9, "!").prepend_right(9, "?").remove(10, 11);
+  assert_eq!(s.chunks.len(), 6);
+  s.reset(0, 9).compact();
+  // `abcdefghi`, `j` with the intro `?`, `k` and `l`
+  assert_eq!(s.chunks.len(), 4);
+  assert_eq!(s.to_string(), "abcdefghi?jl");
+  s.reset(9, 12).compact();
+  assert_eq!(s.chunks.len(), 1);
+  assert_eq!(s.to_string(), "abcdefghijkl");
+}
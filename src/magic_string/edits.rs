@@ -0,0 +1,208 @@
+use crate::{chunk::Chunk, span::Span, CowStr, MagicString, MagicStringError};
+
+use super::{marker::Bias, update::UpdateOptions};
+
+/// An edit of the original source, see [MagicString::edits].
+///
+/// All indices are byte offsets of the original source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edit {
+  /// `content` inserted at `at`, by [MagicString::append_left] for [Bias::Left] and by
+  /// [MagicString::append_right] for [Bias::Right].
+  Insert {
+    at: usize,
+    bias: Bias,
+    content: String,
+  },
+  /// `start..end` replaced with `content`. `keep_original` keeps the original name in sourcemaps.
+  Replace {
+    start: usize,
+    end: usize,
+    content: String,
+    keep_original: bool,
+  },
+  Remove {
+    start: usize,
+    end: usize,
+  },
+  /// `start..end` moved to `to`, like [MagicString::relocate] does.
+  Relocate {
+    start: usize,
+    end: usize,
+    to: usize,
+  },
+}
+
+impl<'text> MagicString<'text> {
+  /// Returns the edits that turn the original source into the generated content, reconstructed
+  /// from the current state rather than recorded, so undone or overwritten edits aren't included.
+  ///
+  /// Replaying them in order with [MagicString::from_edits] gives the same content, with every char
+  /// mapped to the same original position.
+  /// Replacements and removals come first, in the original order. Relocations follow in the order
+  /// they need to be applied, with as few of them as possible. Insertions come last, in the
+  /// original order.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::{Bias, Edit, MagicString};
+  /// let mut s = MagicString::new("abcdef");
+  /// s.remove(0, 1).update(1, 2, "X").update(1, 2, "B").append_left(6, "!").relocate(4, 6, 0);
+  /// assert_eq!(s.to_string(), "ef!Bcd");
+  /// assert_eq!(
+  ///   s.edits(),
+  ///   [
+  ///     Edit::Remove { start: 0, end: 1 },
+  ///     Edit::Replace { start: 1, end: 2, content: "B".to_string(), keep_original: false },
+  ///     Edit::Relocate { start: 4, end: 6, to: 0 },
+  ///     Edit::Insert { at: 6, bias: Bias::Left, content: "!".to_string() },
+  ///   ]
+  /// );
+  /// assert_eq!(MagicString::from_edits("abcdef", s.edits()).to_string(), "ef!Bcd");
+  /// ```
+  pub fn edits(&self) -> Vec<Edit> {
+    let mut edits = vec![];
+    let chunks_in_original_order = || self.chunk_by_start.values().map(|idx| &self.chunks[*idx]);
+
+    // Splitting an edited chunk leaves behind chunks that continue the edit, and adjacent removed
+    // chunks are one removal.
+    let mut replacement: Option<(Span, &str, bool)> = None;
+    let flush = |replacement: Option<(Span, &str, bool)>, edits: &mut Vec<Edit>| {
+      if let Some((Span(start, end), content, keep_original)) = replacement {
+        edits.push(if content.is_empty() && !keep_original {
+          Edit::Remove { start, end }
+        } else {
+          Edit::Replace { start, end, content: content.to_string(), keep_original }
+        });
+      }
+    };
+    for chunk in chunks_in_original_order() {
+      match chunk.edited_content(&self.frag_arena) {
+        Some(content) => match &mut replacement {
+          Some((span, pending_content, keep_original))
+            if chunk.continues_edit
+              || (pending_content.is_empty()
+                && !*keep_original
+                && content.is_empty()
+                && !chunk.keep_in_mappings) =>
+          {
+            span.1 = chunk.end();
+          }
+          _ => {
+            flush(replacement.take(), &mut edits);
+            replacement = Some((chunk.span, content, chunk.keep_in_mappings));
+          }
+        },
+        None => flush(replacement.take(), &mut edits),
+      }
+    }
+    flush(replacement, &mut edits);
+
+    self.relocations_into(&mut edits);
+
+    let mut insert = |at, bias, frags| {
+      for content in self.frag_arena.iter(frags).filter(|content| !content.is_empty()) {
+        edits.push(Edit::Insert { at, bias, content: content.to_string() });
+      }
+    };
+    insert(0, Bias::Left, self.intro);
+    for chunk in chunks_in_original_order() {
+      insert(chunk.start(), Bias::Right, chunk.intro);
+      insert(chunk.end(), Bias::Left, chunk.outro);
+    }
+    insert(self.source.len(), Bias::Right, self.outro);
+    edits
+  }
+
+  /// Replay `edits` on `source` in order. See [MagicString::edits].
+  pub fn from_edits(
+    source: impl Into<CowStr<'text>>,
+    edits: impl IntoIterator<Item = Edit>,
+  ) -> Self {
+    Self::try_from_edits(source, edits).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::from_edits].
+  pub fn try_from_edits(
+    source: impl Into<CowStr<'text>>,
+    edits: impl IntoIterator<Item = Edit>,
+  ) -> Result<Self, MagicStringError> {
+    let mut s = Self::new(source);
+    for edit in edits {
+      match edit {
+        Edit::Insert { at, bias: Bias::Left, content } => s.try_append_left(at, content)?,
+        Edit::Insert { at, bias: Bias::Right, content } => s.try_append_right(at, content)?,
+        Edit::Replace { start, end, content, keep_original } => {
+          s.try_update_with(start, end, content, UpdateOptions { keep_original, overwrite: false })?
+        }
+        Edit::Remove { start, end } => s.try_remove(start, end)?,
+        Edit::Relocate { start, end, to } => s.try_relocate(start, end, to)?,
+      };
+    }
+    Ok(s)
+  }
+
+  // --- private
+
+  /// Push the relocations that turn the original order of the chunks into the current one.
+  ///
+  /// The chunks that are in the longest increasing run of starts stay where they are. The others
+  /// are grouped into blocks that are contiguous in both orders, and moved in reverse, each right
+  /// before the chunk that follows it.
+  fn relocations_into(&self, edits: &mut Vec<Edit>) {
+    let chunks: Vec<&Chunk> = self.iter_chunks().collect();
+    let starts: Vec<usize> = chunks.iter().map(|chunk| chunk.start()).collect();
+    let stays = longest_increasing_subsequence(&starts);
+
+    // The span of each block and the start of the chunk after it
+    let mut blocks: Vec<(Span, Option<usize>)> = vec![];
+    for (i, chunk) in chunks.iter().enumerate() {
+      if stays[i] {
+        continue;
+      }
+      let next_start = chunks.get(i + 1).map(|next| next.start());
+      match blocks.last_mut() {
+        Some((span, block_next_start))
+          if *block_next_start == Some(chunk.start()) && span.end() == chunk.start() =>
+        {
+          span.1 = chunk.end();
+          *block_next_start = next_start;
+        }
+        _ => blocks.push((chunk.span, next_start)),
+      }
+    }
+
+    for (Span(start, end), next_start) in blocks.into_iter().rev() {
+      let to = next_start.unwrap_or(self.source.len());
+      // The block is already right before the chunk that follows it
+      if to != end {
+        edits.push(Edit::Relocate { start, end, to });
+      }
+    }
+  }
+}
+
+/// Returns which of `values` are part of a longest strictly increasing subsequence.
+fn longest_increasing_subsequence(values: &[usize]) -> Vec<bool> {
+  // `tails[k]` is the index of the smallest value that ends an increasing subsequence of length
+  // `k + 1`
+  let mut tails: Vec<usize> = vec![];
+  let mut prev = vec![None; values.len()];
+  for (i, value) in values.iter().enumerate() {
+    let len = tails.partition_point(|&tail| values[tail] < *value);
+    prev[i] = len.checked_sub(1).map(|len| tails[len]);
+    if len == tails.len() {
+      tails.push(i);
+    } else {
+      tails[len] = i;
+    }
+  }
+  let mut is_part = vec![false; values.len()];
+  let mut next = tails.last().copied();
+  while let Some(i) = next {
+    is_part[i] = true;
+    next = prev[i];
+  }
+  is_part
+}
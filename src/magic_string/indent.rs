@@ -72,7 +72,7 @@ impl<'text> MagicString<'text> {
   }
 
   pub fn indent_with(&mut self, opts: IndentOptions) -> &mut Self {
-    if opts.indentor.map_or(false, |s| s.is_empty()) {
+    if opts.indentor.is_some_and(|s| s.is_empty()) {
       return self;
     }
     struct IndentReplacer {
@@ -94,13 +94,15 @@ impl<'text> MagicString<'text> {
       *frag = Cow::Owned(indented);
     }
 
+    self.compact_if_needed();
     let indentor = opts.indentor.unwrap_or_else(|| self.guessed_indentor());
 
     let mut indent_replacer =
       IndentReplacer { should_indent_next_char: true, indentor: indentor.to_string() };
 
-    for intro_frag in self.intro.iter_mut() {
-      indent_frag(intro_frag, &mut indent_replacer)
+    let intro_indices: Vec<_> = self.frag_arena.indices(self.intro).collect();
+    for idx in intro_indices {
+      indent_frag(self.frag_arena.get_mut(idx), &mut indent_replacer)
     }
 
     let exclude_set = ExcludeSet::new(opts.exclude);
@@ -111,9 +113,9 @@ impl<'text> MagicString<'text> {
       // Make sure the `next_chunk_id` is updated before we split the chunk. Otherwise, we
       // might process the same chunk twice.
       next_chunk_id = self.chunks[chunk_idx].next;
-      if let Some(edited_content) = self.chunks[chunk_idx].edited_content.as_mut() {
+      if let Some(edited_idx) = self.chunks[chunk_idx].edited_content {
         if !exclude_set.contains(char_index) {
-          indent_frag(edited_content, &mut indent_replacer);
+          indent_frag(self.frag_arena.get_mut(edited_idx), &mut indent_replacer);
         }
       } else {
         let chunk = &self.chunks[chunk_idx];
@@ -121,7 +123,7 @@ impl<'text> MagicString<'text> {
         char_index = chunk.start();
         let chunk_end = chunk.end();
         for char in chunk.span.text(&self.source).chars() {
-          debug_assert!(self.source.is_char_boundary(char_index as usize));
+          debug_assert!(self.source.is_char_boundary(char_index));
           if !exclude_set.contains(char_index) {
             if char == '\n' {
               indent_replacer.should_indent_next_char = true;
@@ -134,14 +136,19 @@ impl<'text> MagicString<'text> {
           char_index += char.len_utf8();
         }
         for line_start in line_starts {
-          self.prepend_right(line_start, indent_replacer.indentor.clone());
+          // Not `prepend_right`, which might compact the chunks while `next_chunk_id` is held.
+          // `line_start` is the index of a char of an unedited chunk, so splitting can't fail and
+          // there's a chunk starting there.
+          let (chunk, arena) = self.by_start_mut(line_start).unwrap().unwrap();
+          chunk.prepend_intro(arena, indent_replacer.indentor.clone().into());
         }
         char_index = chunk_end;
       }
     }
 
-    for frag in self.outro.iter_mut() {
-      indent_frag(frag, &mut indent_replacer)
+    let outro_indices: Vec<_> = self.frag_arena.indices(self.outro).collect();
+    for idx in outro_indices {
+      indent_frag(self.frag_arena.get_mut(idx), &mut indent_replacer)
     }
 
     self
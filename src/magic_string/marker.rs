@@ -0,0 +1,100 @@
+use crate::{MagicString, MagicStringError};
+
+/// Which side of a position a [Marker] sticks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Bias {
+  /// The marker follows the char before the position. Content inserted at the position ends up
+  /// after the marker.
+  Left,
+  /// The marker follows the char after the position. Content inserted at the position ends up
+  /// before the marker.
+  Right,
+}
+
+/// A position of the original source that is tracked through edits, see [MagicString::mark].
+///
+/// A marker belongs to the [MagicString] that created it and to its clones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Marker(usize);
+
+impl<'text> MagicString<'text> {
+  /// Remember the original index `text_index`, so it can be found in the generated content later
+  /// with [MagicString::marker_generated_offset], no matter what was edited or relocated around it.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::{Bias, MagicString};
+  /// let mut s = MagicString::new("import a;\nfoo();");
+  /// let after_imports = s.mark(9, Bias::Left);
+  /// s.prepend("import b;\n").append_left(9, "\nimport c;");
+  /// assert_eq!(s.to_string(), "import b;\nimport a;\nimport c;\nfoo();");
+  /// assert_eq!(s.marker_generated_offset(after_imports), Some(19));
+  /// ```
+  pub fn mark(&mut self, text_index: usize, bias: Bias) -> Marker {
+    self.try_mark(text_index, bias).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::mark].
+  pub fn try_mark(&mut self, text_index: usize, bias: Bias) -> Result<Marker, MagicStringError> {
+    self.check_index(text_index)?;
+    self.markers.push((text_index, bias));
+    Ok(Marker(self.markers.len() - 1))
+  }
+
+  /// Find the byte offset of the generated content that `marker` points to, without rendering the
+  /// content. Returns `None` if the char the marker sticks to was removed.
+  ///
+  /// If that char was replaced, the marker points to the start of the replacement for
+  /// [Bias::Right] and to its end for [Bias::Left]. A [Bias::Left] marker at the start of the
+  /// source and a [Bias::Right] marker at its end stick to no char, so they point to the start and
+  /// the end of the generated content.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `marker` was created by an unrelated [MagicString].
+  pub fn marker_generated_offset(&self, marker: Marker) -> Option<usize> {
+    let (text_index, bias) = self.markers[marker.0];
+    if !self.changed {
+      return Some(text_index);
+    }
+    let anchored_char_index = match bias {
+      Bias::Left => text_index.checked_sub(1),
+      Bias::Right => (text_index < self.source.len()).then_some(text_index),
+    };
+    let Some(anchored_char_index) = anchored_char_index else {
+      return Some(if bias == Bias::Left { 0 } else { self.len() });
+    };
+    // A part of a replacement sticks to the edge of the content that replaced it
+    let (_, &anchored_idx) = self.chunk_by_start.range(..=anchored_char_index).next_back().unwrap();
+    let (text_index, anchored_char_index) = if self.chunks[anchored_idx].continues_edit {
+      let head = &self.chunks[self.edit_head(anchored_idx)];
+      match bias {
+        Bias::Left => (head.end(), head.end() - 1),
+        Bias::Right => (head.start(), head.start()),
+      }
+    } else {
+      (text_index, anchored_char_index)
+    };
+
+    let mut generated_offset = self.frags_len(self.intro);
+    for chunk in self.iter_chunks() {
+      generated_offset += self.frags_len(chunk.intro);
+      let is_in_chunk = chunk.start() <= anchored_char_index && anchored_char_index < chunk.end();
+      match chunk.edited_content(&self.frag_arena) {
+        Some(edited_content) if is_in_chunk => {
+          return match bias {
+            _ if edited_content.is_empty() => None,
+            Bias::Left => Some(generated_offset + edited_content.len()),
+            Bias::Right => Some(generated_offset),
+          };
+        }
+        None if is_in_chunk => return Some(generated_offset + text_index - chunk.start()),
+        Some(edited_content) => generated_offset += edited_content.len(),
+        None => generated_offset += chunk.end() - chunk.start(),
+      }
+      generated_offset += self.frags_len(chunk.outro);
+    }
+    unreachable!("Chunks cover the whole source")
+  }
+}
@@ -1,42 +1,69 @@
 pub mod append;
+pub mod compact;
+pub mod edits;
 pub mod indent;
+pub mod marker;
 pub mod movement;
+pub mod position;
 pub mod prepend;
+pub mod replace;
+pub mod reset;
+pub mod segment;
+pub mod slice;
 #[cfg(feature = "source_map")]
 pub mod source_map;
+pub mod trim;
 pub mod update;
 
-use std::{collections::VecDeque, sync::OnceLock};
+use std::{collections::BTreeMap, fmt, hash::Hasher, io, sync::OnceLock};
 
 use rustc_hash::FxHashMap;
 
 use crate::{
   chunk::{Chunk, ChunkIdx},
+  error::MagicStringError,
+  fragment::{FragmentArena, FragmentList},
   span::Span,
   type_aliases::IndexChunks,
-  CowStr,
+  CowStr, HashPlaceholders,
 };
 
+use self::{marker::Bias, update::OverlapPolicy};
+
 #[derive(Debug, Default)]
 pub struct MagicStringOptions {
   pub filename: Option<String>,
+  /// How to resolve an update or removal that partially overlaps an already edited range.
+  pub overlap_policy: OverlapPolicy,
+  /// Run [MagicString::compact] automatically once there are this many chunks, and again each time
+  /// their number doubles. Compaction is only done on demand by default.
+  pub compact_threshold: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
 pub struct MagicString<'s> {
   pub filename: Option<String>,
-  intro: VecDeque<CowStr<'s>>,
-  outro: VecDeque<CowStr<'s>>,
-  source: CowStr<'s>,
-  chunks: IndexChunks<'s>,
+  intro: FragmentList,
+  outro: FragmentList,
+  pub(crate) source: CowStr<'s>,
+  chunks: IndexChunks,
+  /// The inserted and edited content of the whole string, including `intro` and `outro`.
+  frag_arena: FragmentArena<'s>,
   first_chunk_idx: ChunkIdx,
   last_chunk_idx: ChunkIdx,
-  chunk_by_start: FxHashMap<usize, ChunkIdx>,
+  /// Sorted, so the chunk containing an index can be found in O(log n) regardless of the order of
+  /// edits.
+  chunk_by_start: BTreeMap<usize, ChunkIdx>,
   chunk_by_end: FxHashMap<usize, ChunkIdx>,
   guessed_indentor: OnceLock<String>,
-
-  // This is used to speed up the search for the chunk that contains a given index.
-  last_searched_chunk_idx: ChunkIdx,
+  overlap_policy: OverlapPolicy,
+  /// Set by every edit, so untouched strings can be detected without rendering them. It's never
+  /// cleared, even if the edits are undone.
+  changed: bool,
+  /// The number of chunks that triggers the next automatic compaction, if enabled.
+  next_compaction_at: Option<usize>,
+  /// The original index and bias of each [marker::Marker], which is an index into this list.
+  markers: Vec<(usize, Bias)>,
 }
 
 impl<'text> MagicString<'text> {
@@ -59,11 +86,15 @@ impl<'text> MagicString<'text> {
       first_chunk_idx: initial_chunk_idx,
       last_chunk_idx: initial_chunk_idx,
       chunks,
+      frag_arena: Default::default(),
       chunk_by_start: Default::default(),
       chunk_by_end: Default::default(),
       filename: options.filename,
       guessed_indentor: OnceLock::default(),
-      last_searched_chunk_idx: initial_chunk_idx,
+      overlap_policy: options.overlap_policy,
+      changed: false,
+      next_compaction_at: options.compact_threshold,
+      markers: Vec::new(),
     };
 
     magic_string.chunk_by_start.insert(0, initial_chunk_idx);
@@ -73,45 +104,137 @@ impl<'text> MagicString<'text> {
   }
 
   pub fn len(&self) -> usize {
+    if !self.changed {
+      return self.source.len();
+    }
     self.fragments().map(|f| f.len()).sum()
   }
 
+  /// Whether the generated content is empty. Unlike `to_string().is_empty()`, it doesn't allocate.
+  pub fn is_empty(&self) -> bool {
+    if !self.changed {
+      return self.source.is_empty();
+    }
+    self.fragments().all(|f| f.is_empty())
+  }
+
+  /// Returns `true` if any edit has been made since the [MagicString] was created.
+  ///
+  /// This is a cheap check that doesn't render the content. Edits without a visible effect, such as
+  /// appending an empty string or resetting earlier edits, still count as changes.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("abc");
+  /// assert!(!s.has_changed());
+  /// s.append_left(1, "!");
+  /// assert!(s.has_changed());
+  /// ```
+  pub fn has_changed(&self) -> bool {
+    self.changed
+  }
+
+  /// The original source, which is never affected by edits.
+  pub fn original(&self) -> &str {
+    &self.source
+  }
+
+  /// Render the generated content into a [String] allocated at its final size up front, which the
+  /// [std::string::ToString] implementation that comes with [fmt::Display] can't do.
+  #[allow(clippy::inherent_to_string_shadow_display)]
   pub fn to_string(&self) -> String {
-    let size_hint = self.len();
-    let mut ret = String::with_capacity(size_hint);
+    let mut ret = String::with_capacity(self.len());
     self.fragments().for_each(|f| ret.push_str(f));
     ret
   }
 
+  /// Write the generated content to `w` piece by piece, without rendering it into a [String] first.
+  pub fn write_to(&self, w: &mut impl io::Write) -> io::Result<()> {
+    self.fragments().try_for_each(|f| w.write_all(f.as_bytes()))
+  }
+
+  /// Like [MagicString::write_to], but for a [fmt::Write].
+  pub fn write_fmt_to(&self, w: &mut impl fmt::Write) -> fmt::Result {
+    self.fragments().try_for_each(|f| w.write_str(f))
+  }
+
+  /// Feed the generated content into `state` piece by piece, without rendering it.
+  ///
+  /// The result equals `state.write(self.to_string().as_bytes())` only for hashers where splitting
+  /// the bytes into several writes doesn't matter, such as [std::hash::DefaultHasher]. Hashers that
+  /// process each write on its own, like `FxHasher`, produce a different but still deterministic
+  /// result. It never equals `self.to_string().hash(state)`, since hashing a [str] also writes a
+  /// trailing `0xff`.
+  pub fn hash_into<H: Hasher>(&self, state: &mut H) {
+    self.fragments().for_each(|f| state.write(f.as_bytes()))
+  }
+
+  /// Like [MagicString::hash_into], but every placeholder of `placeholders` is hashed as the same
+  /// text, so the hash doesn't change with the placeholders used or the values they resolve to.
+  pub fn hash_normalized_into<H: Hasher>(&self, placeholders: &HashPlaceholders, state: &mut H) {
+    placeholders.hash_normalized(self.fragments(), state)
+  }
+
   // --- private
 
   fn prepend_intro(&mut self, content: impl Into<CowStr<'text>>) {
-    self.intro.push_front(content.into());
+    self.changed = true;
+    self.frag_arena.push_front(&mut self.intro, content.into());
   }
 
   fn append_outro(&mut self, content: impl Into<CowStr<'text>>) {
-    self.outro.push_back(content.into());
+    self.changed = true;
+    self.frag_arena.push_back(&mut self.outro, content.into());
   }
 
   fn prepend_outro(&mut self, content: impl Into<CowStr<'text>>) {
-    self.outro.push_front(content.into());
+    self.changed = true;
+    self.frag_arena.push_front(&mut self.outro, content.into());
   }
 
   fn append_intro(&mut self, content: impl Into<CowStr<'text>>) {
-    self.intro.push_back(content.into());
+    self.changed = true;
+    self.frag_arena.push_back(&mut self.intro, content.into());
   }
 
   fn iter_chunks(&self) -> impl Iterator<Item = &Chunk> {
     IterChunks { next: Some(self.first_chunk_idx), chunks: &self.chunks }
   }
 
-  pub(crate) fn fragments(&'text self) -> impl Iterator<Item = &'text str> {
-    let intro = self.intro.iter().map(|s| s.as_ref());
-    let outro = self.outro.iter().map(|s| s.as_ref());
-    let chunks = self.iter_chunks().flat_map(|c| c.fragments(&self.source));
+  /// The total length of the fragments of `frags`.
+  fn frags_len(&self, frags: FragmentList) -> usize {
+    self.frag_arena.iter(frags).map(|frag| frag.len()).sum()
+  }
+
+  pub(crate) fn fragments(&self) -> impl Iterator<Item = &str> {
+    let intro = self.frag_arena.iter(self.intro);
+    let outro = self.frag_arena.iter(self.outro);
+    let chunks = self.iter_chunks().flat_map(|c| c.fragments(&self.frag_arena, &self.source));
     intro.chain(chunks).chain(outro)
   }
 
+  /// Make sure `text_index` is a valid position to edit in the original source.
+  fn check_index(&self, text_index: usize) -> Result<(), MagicStringError> {
+    if text_index > self.source.len() {
+      Err(MagicStringError::OutOfBounds { index: text_index, len: self.source.len() })
+    } else if !self.source.is_char_boundary(text_index) {
+      Err(MagicStringError::NotCharBoundary { index: text_index })
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Make sure `start..end` is a valid range to edit in the original source.
+  fn check_range(&self, start: usize, end: usize) -> Result<(), MagicStringError> {
+    self.check_index(start)?;
+    self.check_index(end)?;
+    if start > end {
+      return Err(MagicStringError::InvalidRange { start, end });
+    }
+    Ok(())
+  }
+
   /// For input
   /// "abcdefg"
   ///  0123456
@@ -122,36 +245,17 @@ impl<'text> MagicString<'text> {
   ///
   /// Chunk{span: (0, 3)} => "abc"
   /// Chunk{span: (3, 7)} => "defg"
-  fn split_at(&mut self, at_index: usize) {
-    if at_index == 0 || at_index >= self.source.len() || self.chunk_by_end.contains_key(&at_index) {
-      return;
-    }
-
-    let (mut candidate, mut candidate_idx, search_right) = {
-      let last_searched_chunk = &self.chunks[self.last_searched_chunk_idx];
-      let search_right = at_index > last_searched_chunk.end();
-
-      (last_searched_chunk, self.last_searched_chunk_idx, search_right)
+  fn split_at(&mut self, at_index: usize) -> Result<(), MagicStringError> {
+    self.check_index(at_index)?;
+    let Some(candidate_idx) = self.chunk_containing(at_index) else {
+      return Ok(());
     };
 
-    while !candidate.contains(at_index) {
-      let next_idx = if search_right {
-        self.chunk_by_start[&candidate.end()]
-      } else {
-        self.chunk_by_end[&candidate.start()]
-      };
-      candidate = &self.chunks[next_idx];
-      candidate_idx = next_idx;
-    }
-
-    let second_half_chunk = self.chunks[candidate_idx].split(at_index);
+    let second_half_chunk = self.chunks[candidate_idx].split(&mut self.frag_arena, at_index);
     let second_half_span = second_half_chunk.span;
     let second_half_idx = self.chunks.push(second_half_chunk);
     let first_half_idx = candidate_idx;
 
-    // Update the last searched chunk
-    self.last_searched_chunk_idx = first_half_idx;
-
     // Update the chunk_by_start/end maps
     self.chunk_by_end.insert(at_index, first_half_idx);
     self.chunk_by_start.insert(at_index, second_half_idx);
@@ -167,38 +271,106 @@ impl<'text> MagicString<'text> {
     if first_half_idx == self.last_chunk_idx {
       self.last_chunk_idx = second_half_idx
     }
+    Ok(())
   }
 
-  fn by_start_mut(&mut self, text_index: usize) -> Option<&mut Chunk<'text>> {
+  /// Find the chunk that `text_index` falls strictly inside of. Returns `None` if `text_index` is
+  /// already at a chunk boundary.
+  fn chunk_containing(&self, text_index: usize) -> Option<ChunkIdx> {
+    if text_index >= self.source.len() {
+      return None;
+    }
+    // The chunk starting at or before `text_index`
+    let (_, &candidate_idx) = self.chunk_by_start.range(..=text_index).next_back()?;
+    self.chunks[candidate_idx].contains(text_index).then_some(candidate_idx)
+  }
+
+  /// Find the first chunk, in the original order, of the replacement that `chunk_idx` is a part of.
+  fn edit_head(&self, mut chunk_idx: ChunkIdx) -> ChunkIdx {
+    while self.chunks[chunk_idx].continues_edit {
+      chunk_idx = self.chunk_by_end[&self.chunks[chunk_idx].start()];
+    }
+    chunk_idx
+  }
+
+  /// Find the replacement with non-empty content that `text_index` falls strictly inside of, even
+  /// if its chunk was split at `text_index`. Returns its first chunk and the span it replaced.
+  fn replacement_around(&self, text_index: usize) -> Option<(ChunkIdx, Span)> {
+    let continues_edit = |idx: &&ChunkIdx| self.chunks[**idx].continues_edit;
+    let chunk_idx = match self.chunk_containing(text_index) {
+      Some(idx) => idx,
+      None => *self.chunk_by_start.get(&text_index).filter(continues_edit)?,
+    };
+    let head_idx = self.edit_head(chunk_idx);
+    let head = &self.chunks[head_idx];
+    if !head.is_edited() || head.is_removed(&self.frag_arena) {
+      return None;
+    }
+    Some((head_idx, self.edited_span(chunk_idx)))
+  }
+
+  /// The span replaced by the edit that `chunk_idx` is a part of, which might have been split into
+  /// several chunks.
+  fn edited_span(&self, chunk_idx: ChunkIdx) -> Span {
+    let head = &self.chunks[self.edit_head(chunk_idx)];
+    let mut end = self.chunks[chunk_idx].end();
+    while let Some(&next_idx) = self.chunk_by_start.get(&end) {
+      if !self.chunks[next_idx].continues_edit {
+        break;
+      }
+      end = self.chunks[next_idx].end();
+    }
+    Span(head.start(), end)
+  }
+
+  /// Returns the chunk starting at `text_index` along with the arena its content lives in.
+  fn by_start_mut(
+    &mut self,
+    text_index: usize,
+  ) -> Result<Option<ChunkMut<'_, 'text>>, MagicStringError> {
+    self.split_at(text_index)?;
+    self.changed = true;
     if text_index == self.source.len() {
-      None
+      Ok(None)
     } else {
-      self.split_at(text_index);
       // TODO: safety: using `unwrap_unchecked` is fine.
       let idx = self.chunk_by_start.get(&text_index).unwrap();
-      Some(&mut self.chunks[*idx])
+      Ok(Some((&mut self.chunks[*idx], &mut self.frag_arena)))
     }
   }
 
-  fn by_end_mut(&mut self, text_index: usize) -> Option<&mut Chunk<'text>> {
+  /// Returns the chunk ending at `text_index` along with the arena its content lives in.
+  fn by_end_mut(
+    &mut self,
+    text_index: usize,
+  ) -> Result<Option<ChunkMut<'_, 'text>>, MagicStringError> {
+    self.split_at(text_index)?;
+    self.changed = true;
     if text_index == 0 {
-      None
+      Ok(None)
     } else {
-      self.split_at(text_index);
       // TODO: safety: using `unwrap_unchecked` is fine.
       let idx = self.chunk_by_end.get(&text_index).unwrap();
-      Some(&mut self.chunks[*idx])
+      Ok(Some((&mut self.chunks[*idx], &mut self.frag_arena)))
     }
   }
 }
 
+impl fmt::Display for MagicString<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.write_fmt_to(f)
+  }
+}
+
+type ChunkMut<'a, 'text> = (&'a mut Chunk, &'a mut FragmentArena<'text>);
+
 struct IterChunks<'a> {
   next: Option<ChunkIdx>,
-  chunks: &'a IndexChunks<'a>,
+  chunks: &'a IndexChunks,
 }
 
 impl<'a> Iterator for IterChunks<'a> {
-  type Item = &'a Chunk<'a>;
+  type Item = &'a Chunk;
 
   fn next(&mut self) -> Option<Self::Item> {
     match self.next {
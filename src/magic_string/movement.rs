@@ -1,30 +1,52 @@
-use crate::MagicString;
+use crate::{MagicString, MagicStringError};
 
 use super::update::UpdateOptions;
 
 impl<'text> MagicString<'text> {
   pub fn remove(&mut self, start: usize, end: usize) -> &mut Self {
+    self.try_remove(start, end).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::remove].
+  pub fn try_remove(&mut self, start: usize, end: usize) -> Result<&mut Self, MagicStringError> {
+    self.check_range(start, end)?;
+    if start == end {
+      return Ok(self);
+    }
     self.inner_update_with(
       start,
       end,
       "".into(),
       UpdateOptions { keep_original: false, overwrite: true },
-      false,
-    );
-
-    self
+    )
   }
 
   /// Moves the characters from start and end to index. Returns this.
   // `move` is reserved keyword in rust, so we use `relocate` instead.
   pub fn relocate(&mut self, start: usize, end: usize, to: usize) -> &mut Self {
+    self.try_relocate(start, end, to).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::relocate].
+  pub fn try_relocate(
+    &mut self,
+    start: usize,
+    end: usize,
+    to: usize,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.check_range(start, end)?;
+    self.check_index(to)?;
     if to >= start && to <= end {
-      panic!("Cannot relocate a selection inside itself")
+      return Err(MagicStringError::MoveIntoSelf { start, end, to });
+    }
+    if start == end {
+      return Ok(self);
     }
 
-    self.split_at(start);
-    self.split_at(end);
-    self.split_at(to);
+    self.compact_if_needed();
+    self.split_at(start)?;
+    self.split_at(end)?;
+    self.split_at(to)?;
 
     let first_idx = self.chunk_by_start[&start];
     let last_idx = self.chunk_by_end[&end];
@@ -37,7 +59,7 @@ impl<'text> MagicString<'text> {
     // `new_right_idx` is `None` means that the `to` index is at the end of the string.
     // Moving chunks which contain the last chunk to the end is meaningless.
     if new_right_idx.is_none() && last_idx == self.last_chunk_idx {
-      return self;
+      return Ok(self);
     }
 
     let new_left_idx = new_right_idx
@@ -45,6 +67,11 @@ impl<'text> MagicString<'text> {
       // If the `to` index is at the end of the string, then the `new_right_idx` will be `None`.
       // In this case, we want to use the last chunk as the left chunk to connect the relocated chunk.
       .unwrap_or(Some(self.last_chunk_idx));
+    // The chunks were already moved right before `to`.
+    if new_left_idx == Some(last_idx) {
+      return Ok(self);
+    }
+    self.changed = true;
 
     // Adjust next/prev pointers, this remove the [start, end] range from the old position
     if let Some(old_left_idx) = old_left_idx {
@@ -81,6 +108,6 @@ impl<'text> MagicString<'text> {
     self.chunks[first_idx].prev = new_left_idx;
     self.chunks[last_idx].next = new_right_idx;
 
-    self
+    Ok(self)
   }
 }
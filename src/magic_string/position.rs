@@ -0,0 +1,212 @@
+use crate::{span::Span, MagicString};
+
+/// Where a position of the generated content comes from, see [MagicString::original_position_for].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OriginalPosition {
+  /// The position is in unedited content, at this byte offset of the original source.
+  Original(usize),
+  /// The position is in the content that replaced `original_span`.
+  Edited { original_span: Span },
+  /// The position is in inserted content. `at` is the original index it was inserted at, or `None`
+  /// for content added with [MagicString::prepend] or [MagicString::append].
+  Inserted { at: Option<usize> },
+}
+
+/// A zero-based line and column, see [MagicString::generated_position_for].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn {
+  pub line: usize,
+  /// Counted in utf-16 code units, like the columns of sourcemaps.
+  pub column: usize,
+}
+
+impl<'text> MagicString<'text> {
+  /// Find where the byte offset `generated_offset` of the generated content comes from, without
+  /// rendering the content. Returns `None` if `generated_offset` is out of bounds.
+  ///
+  /// The end of the generated content belongs to the content right before it.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::{MagicString, OriginalPosition, Span};
+  /// let mut s = MagicString::new("abcdefghijkl");
+  /// s.update(3, 6, "DEF").remove(6, 9).prepend_right(9, ">");
+  /// assert_eq!(s.to_string(), "abcDEF>jkl");
+  /// assert_eq!(s.original_position_for(1), Some(OriginalPosition::Original(1)));
+  /// assert_eq!(
+  ///   s.original_position_for(4),
+  ///   Some(OriginalPosition::Edited { original_span: Span(3, 6) })
+  /// );
+  /// assert_eq!(s.original_position_for(6), Some(OriginalPosition::Inserted { at: Some(9) }));
+  /// assert_eq!(s.original_position_for(7), Some(OriginalPosition::Original(9)));
+  /// ```
+  pub fn original_position_for(&self, generated_offset: usize) -> Option<OriginalPosition> {
+    if !self.changed {
+      return (generated_offset <= self.source.len())
+        .then_some(OriginalPosition::Original(generated_offset));
+    }
+
+    let mut frag_start = 0;
+    // The position of the end of the last non-empty fragment
+    let mut end_position = None;
+    let mut find_in = |frag: &str, position_at: &dyn Fn(usize) -> OriginalPosition| {
+      if frag.is_empty() {
+        return None;
+      }
+      let frag_end = frag_start + frag.len();
+      if generated_offset < frag_end {
+        return Some(position_at(generated_offset - frag_start));
+      }
+      frag_start = frag_end;
+      end_position = Some(position_at(frag.len()));
+      None
+    };
+
+    let global_inserted = |_| OriginalPosition::Inserted { at: None };
+    for frag in self.frag_arena.iter(self.intro) {
+      if let Some(position) = find_in(frag, &global_inserted) {
+        return Some(position);
+      }
+    }
+    for chunk in self.iter_chunks() {
+      let intro_inserted = |_| OriginalPosition::Inserted { at: Some(chunk.start()) };
+      for frag in self.frag_arena.iter(chunk.intro) {
+        if let Some(position) = find_in(frag, &intro_inserted) {
+          return Some(position);
+        }
+      }
+      let position = match chunk.edited_content(&self.frag_arena) {
+        Some(edited_content) => find_in(edited_content, &|_| OriginalPosition::Edited {
+          original_span: self.edited_span(self.chunk_by_start[&chunk.start()]),
+        }),
+        None => find_in(chunk.span.text(&self.source), &|offset| {
+          OriginalPosition::Original(chunk.start() + offset)
+        }),
+      };
+      if position.is_some() {
+        return position;
+      }
+      let outro_inserted = |_| OriginalPosition::Inserted { at: Some(chunk.end()) };
+      for frag in self.frag_arena.iter(chunk.outro) {
+        if let Some(position) = find_in(frag, &outro_inserted) {
+          return Some(position);
+        }
+      }
+    }
+    for frag in self.frag_arena.iter(self.outro) {
+      if let Some(position) = find_in(frag, &global_inserted) {
+        return Some(position);
+      }
+    }
+
+    (generated_offset == frag_start).then_some(end_position).flatten()
+  }
+
+  /// Find the byte offset of the generated content where the char at `original_offset` of the
+  /// original source ended up, without rendering the content.
+  ///
+  /// Returns `None` if `original_offset` is out of bounds or if the original text was removed. An
+  /// offset inside of a replaced range maps to the start of its replacement.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("abcdefghijkl");
+  /// s.update(3, 6, "DEF").remove(6, 9).prepend_right(9, ">").relocate(0, 3, 12);
+  /// assert_eq!(s.to_string(), "DEF>jklabc");
+  /// assert_eq!(s.generated_offset_for(1), Some(8));
+  /// assert_eq!(s.generated_offset_for(4), Some(0));
+  /// assert_eq!(s.generated_offset_for(7), None);
+  /// assert_eq!(s.generated_offset_for(9), Some(4));
+  /// ```
+  pub fn generated_offset_for(&self, original_offset: usize) -> Option<usize> {
+    self.check_index(original_offset).ok()?;
+    if !self.changed {
+      return Some(original_offset);
+    }
+    let original_offset = match self.chunk_by_start.range(..=original_offset).next_back() {
+      Some((_, &idx)) if self.chunks[idx].continues_edit => {
+        self.chunks[self.edit_head(idx)].start()
+      }
+      _ => original_offset,
+    };
+
+    let mut generated_offset = self.frags_len(self.intro);
+    for chunk in self.iter_chunks() {
+      generated_offset += self.frags_len(chunk.intro);
+      // The end of the source belongs to the last chunk in the original order
+      let is_in_chunk = (chunk.start() <= original_offset && original_offset < chunk.end())
+        || original_offset == chunk.end() && original_offset == self.source.len();
+      match chunk.edited_content(&self.frag_arena) {
+        Some(edited_content) if is_in_chunk => {
+          return (!edited_content.is_empty()).then_some(generated_offset);
+        }
+        None if is_in_chunk => return Some(generated_offset + original_offset - chunk.start()),
+        Some(edited_content) => generated_offset += edited_content.len(),
+        None => generated_offset += chunk.end() - chunk.start(),
+      }
+      generated_offset += self.frags_len(chunk.outro);
+    }
+    unreachable!("Chunks cover the whole source")
+  }
+
+  /// Line and column version of [MagicString::generated_offset_for].
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::{LineColumn, MagicString};
+  /// let mut s = MagicString::new("let a = 1;\nlet b = a;");
+  /// s.prepend("'use strict';\n").update(11, 14, "const");
+  /// assert_eq!(
+  ///   s.generated_position_for(LineColumn { line: 1, column: 8 }),
+  ///   Some(LineColumn { line: 2, column: 10 })
+  /// );
+  /// ```
+  pub fn generated_position_for(&self, original: LineColumn) -> Option<LineColumn> {
+    let original_offset = offset_of(&self.source, original)?;
+    let generated_offset = self.generated_offset_for(original_offset)?;
+    Some(line_column_of(self.fragments(), generated_offset))
+  }
+}
+
+/// Convert `position` to a byte offset of `text`. Returns `None` if `position` is out of bounds.
+fn offset_of(text: &str, position: LineColumn) -> Option<usize> {
+  let line_start = match position.line.checked_sub(1) {
+    Some(prev_line) => text.match_indices('\n').nth(prev_line)?.0 + 1,
+    None => 0,
+  };
+  let mut column = 0;
+  for (offset, char) in text[line_start..].char_indices() {
+    if column >= position.column {
+      // The column might point into the middle of a surrogate pair
+      return (column == position.column).then_some(line_start + offset);
+    }
+    if char == '\n' {
+      return None;
+    }
+    column += char.len_utf16();
+  }
+  (column == position.column).then_some(text.len())
+}
+
+/// Find the line and column of the byte offset `offset` of the concatenated `frags`.
+fn line_column_of<'a>(frags: impl Iterator<Item = &'a str>, offset: usize) -> LineColumn {
+  let mut position = LineColumn { line: 0, column: 0 };
+  let mut remaining = offset;
+  for frag in frags {
+    if remaining == 0 {
+      break;
+    }
+    let frag = &frag[..remaining.min(frag.len())];
+    for char in frag.chars() {
+      if char == '\n' {
+        position.line += 1;
+        position.column = 0;
+      } else {
+        position.column += char.len_utf16();
+      }
+    }
+    remaining -= frag.len();
+  }
+  position
+}
@@ -1,4 +1,4 @@
-use crate::CowStr;
+use crate::{CowStr, MagicStringError};
 
 use super::MagicString;
 
@@ -13,11 +13,21 @@ impl<'text> MagicString<'text> {
     text_index: usize,
     content: impl Into<CowStr<'text>>,
   ) -> &mut Self {
-    match self.by_end_mut(text_index) {
-      Some(chunk) => chunk.prepend_outro(content.into()),
+    self.try_prepend_left(text_index, content).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::prepend_left].
+  pub fn try_prepend_left(
+    &mut self,
+    text_index: usize,
+    content: impl Into<CowStr<'text>>,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.compact_if_needed();
+    match self.by_end_mut(text_index)? {
+      Some((chunk, arena)) => chunk.prepend_outro(arena, content.into()),
       None => self.prepend_intro(content.into()),
     }
-    self
+    Ok(self)
   }
 
   pub fn prepend_right(
@@ -25,12 +35,22 @@ impl<'text> MagicString<'text> {
     text_index: usize,
     content: impl Into<CowStr<'text>>,
   ) -> &mut Self {
-    match self.by_start_mut(text_index) {
-      Some(chunk) => {
-        chunk.prepend_intro(content.into());
+    self.try_prepend_right(text_index, content).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::prepend_right].
+  pub fn try_prepend_right(
+    &mut self,
+    text_index: usize,
+    content: impl Into<CowStr<'text>>,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.compact_if_needed();
+    match self.by_start_mut(text_index)? {
+      Some((chunk, arena)) => {
+        chunk.prepend_intro(arena, content.into());
       }
       None => self.prepend_outro(content.into()),
     }
-    self
+    Ok(self)
   }
 }
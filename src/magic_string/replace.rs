@@ -0,0 +1,168 @@
+use crate::{CowStr, MagicString, MagicStringError};
+
+impl<'text> MagicString<'text> {
+  /// Replaces the first occurrence of `pattern` in the original source with `replacement`. An empty
+  /// `pattern` matches at the start, so `replacement` is inserted there.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("foo + foo");
+  /// s.replace("foo", "bar");
+  /// assert_eq!(s.to_string(), "bar + foo");
+  /// ```
+  pub fn replace(&mut self, pattern: &str, replacement: impl Into<CowStr<'text>>) -> &mut Self {
+    self.try_replace(pattern, replacement).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::replace].
+  pub fn try_replace(
+    &mut self,
+    pattern: &str,
+    replacement: impl Into<CowStr<'text>>,
+  ) -> Result<&mut Self, MagicStringError> {
+    let first_match = self.source.find(pattern);
+    if let Some(start) = first_match {
+      self.replace_match(start, start + pattern.len(), replacement.into())?;
+    }
+    Ok(self)
+  }
+
+  /// Replaces all occurrences of `pattern` in the original source with `replacement`. An empty
+  /// `pattern` matches around every character, like in JS.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("foo + foo");
+  /// s.replace_all("foo", "bar");
+  /// assert_eq!(s.to_string(), "bar + bar");
+  /// ```
+  pub fn replace_all(&mut self, pattern: &str, replacement: impl Into<CowStr<'text>>) -> &mut Self {
+    self.try_replace_all(pattern, replacement).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::replace_all].
+  ///
+  /// Matches are replaced in order. If one of them fails, e.g. because it partially overlaps an
+  /// edited range, the error is returned and the matches before it stay replaced.
+  pub fn try_replace_all(
+    &mut self,
+    pattern: &str,
+    replacement: impl Into<CowStr<'text>>,
+  ) -> Result<&mut Self, MagicStringError> {
+    let replacement = replacement.into();
+    let matches = self.source.match_indices(pattern).map(|(start, _)| start).collect::<Vec<_>>();
+    for start in matches {
+      self.replace_match(start, start + pattern.len(), replacement.clone())?;
+    }
+    Ok(self)
+  }
+
+  // --- private
+
+  fn replace_match(
+    &mut self,
+    start: usize,
+    end: usize,
+    replacement: CowStr<'text>,
+  ) -> Result<(), MagicStringError> {
+    if self.source[start..end] == *replacement {
+      return Ok(());
+    }
+    if start == end {
+      // An empty match, e.g. of an empty pattern, inserts the replacement like it does in JS.
+      self.try_append_left(start, replacement)?;
+    } else {
+      self.try_update(start, end, replacement)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(feature = "regex")]
+impl<'text> MagicString<'text> {
+  /// Replaces the first match of `re` in the original source. `replacement` follows the rules of
+  /// [regex::Regex::replace], so `$1`-style captures are expanded and closures are supported.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let re = regex::Regex::new(r"(\w+) = (\w+)").unwrap();
+  /// let mut s = MagicString::new("a = b; c = d;");
+  /// s.replace_regex(&re, "$2 = $1");
+  /// assert_eq!(s.to_string(), "b = a; c = d;");
+  /// ```
+  pub fn replace_regex(
+    &mut self,
+    re: &regex::Regex,
+    replacement: impl regex::Replacer,
+  ) -> &mut Self {
+    self.try_replace_regex(re, replacement).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::replace_regex].
+  pub fn try_replace_regex(
+    &mut self,
+    re: &regex::Regex,
+    replacement: impl regex::Replacer,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.replace_regex_matches(re, replacement, 1)
+  }
+
+  /// Replaces all matches of `re` in the original source. `replacement` follows the rules of
+  /// [regex::Regex::replace_all], so `$1`-style captures are expanded and closures are supported.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let re = regex::Regex::new(r"\d+").unwrap();
+  /// let mut s = MagicString::new("a = 1; b = 22;");
+  /// s.replace_all_regex(&re, |caps: &regex::Captures| (caps[0].len()).to_string());
+  /// assert_eq!(s.to_string(), "a = 1; b = 2;");
+  /// ```
+  pub fn replace_all_regex(
+    &mut self,
+    re: &regex::Regex,
+    replacement: impl regex::Replacer,
+  ) -> &mut Self {
+    self.try_replace_all_regex(re, replacement).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::replace_all_regex].
+  ///
+  /// Matches are replaced in order. If one of them fails, e.g. because it partially overlaps an
+  /// edited range, the error is returned and the matches before it stay replaced.
+  pub fn try_replace_all_regex(
+    &mut self,
+    re: &regex::Regex,
+    replacement: impl regex::Replacer,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.replace_regex_matches(re, replacement, usize::MAX)
+  }
+
+  // --- private
+
+  fn replace_regex_matches(
+    &mut self,
+    re: &regex::Regex,
+    mut replacement: impl regex::Replacer,
+    limit: usize,
+  ) -> Result<&mut Self, MagicStringError> {
+    let matches = re
+      .captures_iter(&self.source)
+      .take(limit)
+      .map(|caps| {
+        // The group 0 always exists, it is the whole match.
+        let whole_match = caps.get(0).unwrap();
+        let mut expanded = String::new();
+        replacement.replace_append(&caps, &mut expanded);
+        (whole_match.start(), whole_match.end(), expanded)
+      })
+      .collect::<Vec<_>>();
+    for (start, end, expanded) in matches {
+      self.replace_match(start, end, expanded.into())?;
+    }
+    Ok(self)
+  }
+}
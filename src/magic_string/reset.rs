@@ -0,0 +1,91 @@
+use crate::{MagicString, MagicStringError};
+
+#[derive(Debug, Clone)]
+pub struct ResetOptions {
+  /// `true` will clear the `intro` and `outro` for the corresponding range.
+  pub overwrite: bool,
+}
+
+impl Default for ResetOptions {
+  fn default() -> Self {
+    Self { overwrite: true }
+  }
+}
+
+impl<'text> MagicString<'text> {
+  /// A shorthand for `reset_with(start, end, Default::default())`.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("abcdefghijkl");
+  /// s.remove(1, 5).update(6, 9, "GHI").append_left(9, "!");
+  /// assert_eq!(s.to_string(), "afGHI!jkl");
+  /// s.reset(2, 4).reset(6, 9);
+  /// assert_eq!(s.to_string(), "acdfghijkl");
+  /// ```
+  pub fn reset(&mut self, start: usize, end: usize) -> &mut Self {
+    self.reset_with(start, end, Default::default())
+  }
+
+  /// Fallible version of [MagicString::reset].
+  pub fn try_reset(&mut self, start: usize, end: usize) -> Result<&mut Self, MagicStringError> {
+    self.try_reset_with(start, end, Default::default())
+  }
+
+  /// Restores the original content of `start..end`, undoing updates and removals in the range.
+  /// Relocations are kept.
+  ///
+  /// Neither `start` nor `end` can fall inside of a range replaced with non-empty content, which
+  /// can only be reset as a whole.
+  pub fn reset_with(&mut self, start: usize, end: usize, opts: ResetOptions) -> &mut Self {
+    self.try_reset_with(start, end, opts).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::reset_with].
+  pub fn try_reset_with(
+    &mut self,
+    start: usize,
+    end: usize,
+    opts: ResetOptions,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.check_range(start, end)?;
+    if start == end {
+      return Ok(self);
+    }
+    self.compact_if_needed();
+    for text_index in [start, end] {
+      if let Some((_, edited_span)) = self.replacement_around(text_index) {
+        return Err(MagicStringError::OverlappingEdit {
+          index: text_index,
+          edited_start: edited_span.start(),
+          edited_end: edited_span.end(),
+        });
+      }
+    }
+    self.split_at(start)?;
+    self.split_at(end)?;
+    self.reset_chunks(start, end, opts.overwrite);
+    Ok(self)
+  }
+
+  // --- private
+
+  /// Reset the content of the chunks of `start..end`, which must be chunk boundaries.
+  pub(super) fn reset_chunks(&mut self, start: usize, end: usize, overwrite: bool) {
+    // Walk the range in the original order, since it might have been relocated.
+    let mut chunk_idx = self.chunk_by_start[&start];
+    loop {
+      let chunk = &mut self.chunks[chunk_idx];
+      chunk.reset_content();
+      if overwrite {
+        chunk.intro.clear();
+        chunk.outro.clear();
+      }
+      if chunk.end() == end {
+        break;
+      }
+      chunk_idx = self.chunk_by_start[&chunk.end()];
+    }
+  }
+}
@@ -0,0 +1,83 @@
+use crate::{span::Span, MagicString};
+
+/// Where the text of a [Segment] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+  /// Content added with [MagicString::prepend], or inserted at the start of the source.
+  GlobalIntro,
+  /// Content inserted before the chunk starting at the original index `at`, i.e. with
+  /// [MagicString::prepend_right] or [MagicString::append_right].
+  ChunkIntro { at: usize },
+  /// Unedited content of the original source.
+  Original { span: Span },
+  /// The content that replaced `original_span`. It's empty if the range was removed. `name_kept` is
+  /// `true` if the original name is kept in sourcemaps.
+  Edited { original_span: Span, name_kept: bool },
+  /// Content inserted after the chunk ending at the original index `at`, i.e. with
+  /// [MagicString::prepend_left] or [MagicString::append_left].
+  ChunkOutro { at: usize },
+  /// Content added with [MagicString::append], or inserted at the end of the source.
+  GlobalOutro,
+}
+
+/// A piece of the generated content, see [MagicString::segments].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+  pub text: &'a str,
+  pub kind: SegmentKind,
+}
+
+impl<'text> MagicString<'text> {
+  /// Iterate over the pieces of the generated content in order, along with where each of them comes
+  /// from. Concatenating their `text` gives the generated content.
+  ///
+  /// Each chunk yields an [SegmentKind::Original] or [SegmentKind::Edited] segment, even if it's
+  /// empty, so removed ranges are reported too. A replacement yields a single segment, even if
+  /// later edits split it into several chunks.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::{MagicString, Segment, SegmentKind, Span};
+  /// let mut s = MagicString::new("abcdef");
+  /// s.update(3, 6, "DEF").prepend_right(3, "<").prepend("^");
+  /// assert_eq!(
+  ///   s.segments().collect::<Vec<_>>(),
+  ///   [
+  ///     Segment { text: "^", kind: SegmentKind::GlobalIntro },
+  ///     Segment { text: "abc", kind: SegmentKind::Original { span: Span(0, 3) } },
+  ///     Segment { text: "<", kind: SegmentKind::ChunkIntro { at: 3 } },
+  ///     Segment {
+  ///       text: "DEF",
+  ///       kind: SegmentKind::Edited { original_span: Span(3, 6), name_kept: false }
+  ///     },
+  ///   ]
+  /// );
+  /// ```
+  pub fn segments(&self) -> impl Iterator<Item = Segment<'_>> {
+    let arena = &self.frag_arena;
+    let segments_of = move |frags, kind| arena.iter(frags).map(move |text| Segment { text, kind });
+    let chunks = self.iter_chunks().flat_map(move |chunk| {
+      let content = match chunk.edited_content(arena) {
+        // The first part of the replacement reports all of it
+        _ if chunk.continues_edit => None,
+        Some(edited_content) => Some(Segment {
+          text: edited_content,
+          kind: SegmentKind::Edited {
+            original_span: self.edited_span(self.chunk_by_start[&chunk.start()]),
+            name_kept: chunk.keep_in_mappings,
+          },
+        }),
+        None => Some(Segment {
+          text: chunk.span.text(&self.source),
+          kind: SegmentKind::Original { span: chunk.span },
+        }),
+      };
+      segments_of(chunk.intro, SegmentKind::ChunkIntro { at: chunk.start() })
+        .chain(content)
+        .chain(segments_of(chunk.outro, SegmentKind::ChunkOutro { at: chunk.end() }))
+    });
+    segments_of(self.intro, SegmentKind::GlobalIntro)
+      .chain(chunks)
+      .chain(segments_of(self.outro, SegmentKind::GlobalOutro))
+  }
+}
@@ -0,0 +1,102 @@
+use crate::{MagicString, MagicStringError};
+
+impl<'text> MagicString<'text> {
+  /// Returns the generated content corresponding to the original range `start..end`.
+  ///
+  /// - Content inserted at `start` is included if it belongs to the chunk starting at `start`, i.e.
+  ///   it was added by `append_right` or `prepend_right`. Likewise, content inserted at `end` is
+  ///   included if it was added by `append_left` or `prepend_left`.
+  /// - The chunks are collected in the order they are rendered, from `start` to `end`. Content
+  ///   relocated into that part of the output is included, and content relocated out of it is not.
+  ///   If `end` is rendered before `start`, [MagicStringError::RelocatedRange] is returned.
+  /// - Neither `start` nor `end` could fall inside a range replaced with non-empty content.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("abcdefghijkl");
+  /// s.update(4, 8, "XX").prepend_right(3, "<");
+  /// assert_eq!(s.slice(3, 9).unwrap(), "<dXXi");
+  /// assert_eq!(s.slice(4, 8).unwrap(), "XX");
+  /// assert!(s.slice(5, 9).is_err());
+  /// ```
+  pub fn slice(&self, start: usize, end: usize) -> Result<String, MagicStringError> {
+    self.check_range(start, end)?;
+    let mut ret = String::new();
+    if start == end {
+      return Ok(ret);
+    }
+
+    for text_index in [start, end] {
+      if let Some((_, edited_span)) = self.replacement_around(text_index) {
+        return Err(MagicStringError::OverlappingEdit {
+          index: text_index,
+          edited_start: edited_span.start(),
+          edited_end: edited_span.end(),
+        });
+      }
+    }
+
+    let start_chunk_idx = match self.chunk_containing(start) {
+      Some(idx) => idx,
+      None => self.chunk_by_start[&start],
+    };
+
+    let mut next_chunk_idx = Some(start_chunk_idx);
+    while let Some(chunk_idx) = next_chunk_idx {
+      let chunk = &self.chunks[chunk_idx];
+      let is_start_chunk = chunk_idx == start_chunk_idx;
+      let contains_end = chunk.start() < end && end <= chunk.end();
+
+      if !is_start_chunk || chunk.start() == start {
+        self.frag_arena.iter(chunk.intro).for_each(|frag| ret.push_str(frag));
+      }
+      match chunk.edited_content(&self.frag_arena) {
+        Some(edited_content) => ret.push_str(edited_content),
+        None => {
+          let content_start = if is_start_chunk { start } else { chunk.start() };
+          let content_end = if contains_end { end } else { chunk.end() };
+          ret.push_str(&self.source[content_start..content_end]);
+        }
+      }
+      if !contains_end || chunk.end() == end {
+        self.frag_arena.iter(chunk.outro).for_each(|frag| ret.push_str(frag));
+      }
+
+      if contains_end {
+        return Ok(ret);
+      }
+      next_chunk_idx = chunk.next;
+    }
+
+    Err(MagicStringError::RelocatedRange { start, end })
+  }
+}
+
+impl<'text> MagicString<'text> {
+  /// Returns a clone of this [MagicString] with everything outside of the original range
+  /// `start..end` removed, including content prepended or appended to the whole string. Edits
+  /// inside of the range are kept, and so are the mappings to the original source.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new("let a = 1;\nlet b = 2;");
+  /// s.prepend("// header\n").update(15, 16, "c");
+  /// assert_eq!(s.snip(11, 21).to_string(), "let c = 2;");
+  /// ```
+  pub fn snip(&self, start: usize, end: usize) -> MagicString<'text> {
+    self.try_snip(start, end).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::snip].
+  pub fn try_snip(&self, start: usize, end: usize) -> Result<MagicString<'text>, MagicStringError> {
+    self.check_range(start, end)?;
+    let mut snipped = self.clone();
+    snipped.intro.clear();
+    snipped.outro.clear();
+    snipped.try_remove(0, start)?;
+    snipped.try_remove(end, self.source.len())?;
+    Ok(snipped)
+  }
+}
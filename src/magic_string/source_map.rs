@@ -1,20 +1,51 @@
 use std::sync::Arc;
 
 use crate::{
-  source_map::{locator::Locator, sourcemap_builder::SourcemapBuilder},
+  source_map::{
+    locator::{Location, Locator},
+    remapping::remap,
+    sourcemap_builder::SourcemapBuilder,
+  },
   MagicString,
 };
 
+/// How fine-grained the mappings of unedited content are.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Hires {
+  /// One mapping at the start of each line.
+  #[default]
+  Off,
+  /// One mapping at the start of each word and at every other char but line breaks, such as
+  /// punctuation and whitespace. Word chars are `[A-Za-z0-9_]`, like magic-string's
+  /// `hires: 'boundary'`.
+  Boundary,
+  /// One mapping per char.
+  Char,
+}
+
 #[derive(Debug)]
 pub struct SourceMapOptions {
+  /// `true` will embed the original sources in the `sourcesContent` field of the sourcemap.
   pub include_content: bool,
+  /// The id of the source in the generated sourcemap. It's ignored by [crate::Joiner::source_map],
+  /// which uses the `filename` of each joined [MagicString] instead.
   pub source: Arc<str>,
-  pub hires: bool,
+  pub hires: Hires,
+  /// The `file` field of the sourcemap, i.e. the name of the generated file.
+  pub file: Option<String>,
+  /// The `sourceRoot` field of the sourcemap, which is prepended to each entry of `sources`.
+  pub source_root: Option<String>,
 }
 
 impl Default for SourceMapOptions {
   fn default() -> Self {
-    Self { include_content: false, source: "".into(), hires: false }
+    Self {
+      include_content: false,
+      source: "".into(),
+      hires: Hires::Off,
+      file: None,
+      source_root: None,
+    }
   }
 }
 
@@ -24,30 +55,81 @@ impl<'s> MagicString<'s> {
 
     source_builder.set_source_and_content(&opts.source, &self.source);
 
+    self.add_to_sourcemap_builder(&mut source_builder);
+
+    source_builder.into_source_map(&opts)
+  }
+
+  /// Generate a sourcemap that maps through `input_map` to the sources the original content was
+  /// generated from, for content that was already transformed by an earlier tool.
+  ///
+  /// `opts.source` is ignored, the sources of `input_map` are used instead.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::{MagicString, SourceMapOptions};
+  /// let mut first = MagicString::new("let a: number = 1;");
+  /// first.remove(5, 13);
+  /// let input_map =
+  ///   first.source_map(SourceMapOptions { source: "a.ts".into(), ..Default::default() });
+  ///
+  /// let mut second = MagicString::new(first.to_string());
+  /// second.update(6, 7, "=");
+  /// let sm = second.source_map_chained(SourceMapOptions::default(), &input_map);
+  /// assert_eq!(sm.get_sources().collect::<Vec<_>>(), ["a.ts"]);
+  /// ```
+  pub fn source_map_chained(
+    &self,
+    opts: SourceMapOptions,
+    input_map: &oxc_sourcemap::SourceMap,
+  ) -> oxc_sourcemap::SourceMap {
+    let source_map = self.source_map(SourceMapOptions { hires: opts.hires, ..Default::default() });
+    remap(&source_map, input_map, &opts)
+  }
+
+  // --- private
+
+  /// Emit the mappings of this [MagicString] against the source that is currently selected in
+  /// `source_builder`.
+  pub(crate) fn add_to_sourcemap_builder(&self, source_builder: &mut SourcemapBuilder) {
+    if !self.changed {
+      // The output is the source itself, so there is no need to walk the chunks or locate them.
+      source_builder.add_original(&self.source, Location { line: 0, column: 0 });
+      return;
+    }
+
     let locator = Locator::new(&self.source);
 
-    self.intro.iter().for_each(|frag| {
+    self.frag_arena.iter(self.intro).for_each(|frag| {
       source_builder.advance(frag);
     });
 
     self.iter_chunks().for_each(|chunk| {
-      chunk.intro.iter().for_each(|frag| {
+      self.frag_arena.iter(chunk.intro).for_each(|frag| {
         source_builder.advance(frag);
       });
 
       let name = if chunk.keep_in_mappings && chunk.is_edited() {
-        Some(chunk.span.text(&self.source))
+        Some(self.edited_span(self.chunk_by_start[&chunk.start()]).text(&self.source))
       } else {
         None
       };
 
-      source_builder.add_chunk(chunk, &locator, &self.source, name);
+      source_builder.add_chunk(
+        chunk,
+        chunk.edited_content(&self.frag_arena).map(|content| content.as_ref()),
+        &locator,
+        &self.source,
+        name,
+      );
 
-      chunk.outro.iter().for_each(|frag| {
+      self.frag_arena.iter(chunk.outro).for_each(|frag| {
         source_builder.advance(frag);
       });
     });
 
-    source_builder.into_source_map()
+    self.frag_arena.iter(self.outro).for_each(|frag| {
+      source_builder.advance(frag);
+    });
   }
 }
@@ -0,0 +1,209 @@
+use crate::{
+  chunk::EditOptions,
+  fragment::{FragmentArena, FragmentList},
+  CowStr, MagicString,
+};
+
+/// Trim the start of `frag` in place, without allocating.
+fn trim_frag_start(frag: &mut CowStr, pat: &impl Fn(char) -> bool) {
+  let trimmed_len = frag.len() - frag.trim_start_matches(pat).len();
+  if trimmed_len == 0 {
+    return;
+  }
+  match frag {
+    CowStr::Borrowed(s) => *s = &s[trimmed_len..],
+    CowStr::Owned(s) => {
+      s.drain(..trimmed_len);
+    }
+  }
+}
+
+/// Trim the end of `frag` in place, without allocating.
+fn trim_frag_end(frag: &mut CowStr, pat: &impl Fn(char) -> bool) {
+  let trimmed_len = frag.trim_end_matches(pat).len();
+  match frag {
+    CowStr::Borrowed(s) => *s = &s[..trimmed_len],
+    CowStr::Owned(s) => s.truncate(trimmed_len),
+  }
+}
+
+/// Returns `true` if trimming stopped at a char that doesn't match `pat`.
+fn trim_frags_start(
+  arena: &mut FragmentArena,
+  frags: FragmentList,
+  pat: &impl Fn(char) -> bool,
+) -> bool {
+  let indices: Vec<_> = arena.indices(frags).collect();
+  indices.into_iter().any(|idx| {
+    let frag = arena.get_mut(idx);
+    trim_frag_start(frag, pat);
+    !frag.is_empty()
+  })
+}
+
+/// Returns `true` if trimming stopped at a char that doesn't match `pat`.
+fn trim_frags_end(
+  arena: &mut FragmentArena,
+  frags: FragmentList,
+  pat: &impl Fn(char) -> bool,
+) -> bool {
+  let indices: Vec<_> = arena.indices(frags).collect();
+  indices.into_iter().rev().any(|idx| {
+    let frag = arena.get_mut(idx);
+    trim_frag_end(frag, pat);
+    !frag.is_empty()
+  })
+}
+
+impl<'text> MagicString<'text> {
+  /// Removes leading and trailing whitespace.
+  ///
+  /// Trimmed original content is removed like [MagicString::remove] does, so the rest of the
+  /// content keeps its mappings.
+  pub fn trim(&mut self) -> &mut Self {
+    self.trim_matches(char::is_whitespace)
+  }
+
+  /// Removes leading whitespace.
+  pub fn trim_start(&mut self) -> &mut Self {
+    self.trim_start_matches(char::is_whitespace)
+  }
+
+  /// Removes trailing whitespace.
+  pub fn trim_end(&mut self) -> &mut Self {
+    self.trim_end_matches(char::is_whitespace)
+  }
+
+  /// Removes leading and trailing line breaks.
+  pub fn trim_lines(&mut self) -> &mut Self {
+    self.trim_matches(|c| c == '\r' || c == '\n')
+  }
+
+  /// Removes leading and trailing chars that match `pat`.
+  ///
+  /// # Example
+  /// ```rust
+  /// use string_wizard::MagicString;
+  /// let mut s = MagicString::new(";;let a = 1;;");
+  /// s.prepend(";");
+  /// s.trim_matches(|c| c == ';');
+  /// assert_eq!(s.to_string(), "let a = 1");
+  /// ```
+  pub fn trim_matches(&mut self, pat: impl Fn(char) -> bool) -> &mut Self {
+    self.trim_end_matches(&pat);
+    self.trim_start_matches(&pat)
+  }
+
+  /// Removes leading chars that match `pat`.
+  pub fn trim_start_matches(&mut self, pat: impl Fn(char) -> bool) -> &mut Self {
+    if trim_frags_start(&mut self.frag_arena, self.intro, &pat) {
+      return self;
+    }
+
+    let mut next_chunk_idx = Some(self.first_chunk_idx);
+    while let Some(chunk_idx) = next_chunk_idx {
+      let chunk = &mut self.chunks[chunk_idx];
+      if trim_frags_start(&mut self.frag_arena, chunk.intro, &pat) {
+        return self;
+      }
+      match chunk.edited_content {
+        Some(edited_idx) => {
+          let edited_content = self.frag_arena.get_mut(edited_idx);
+          trim_frag_start(edited_content, &pat);
+          if !edited_content.is_empty() {
+            return self;
+          }
+        }
+        None => {
+          let content = chunk.span.text(&self.source);
+          let trimmed_len = content.len() - content.trim_start_matches(&pat).len();
+          if trimmed_len < content.len() {
+            if trimmed_len > 0 {
+              self.changed = true;
+              let trimmed_end = chunk.start() + trimmed_len;
+              // `trimmed_end` is a char boundary inside of an unedited chunk, so splitting can't
+              // fail.
+              self.split_at(trimmed_end).unwrap();
+              self.chunks[chunk_idx].edit(
+                &mut self.frag_arena,
+                "".into(),
+                EditOptions { overwrite: false, store_name: false },
+              );
+            }
+            return self;
+          }
+          self.changed = true;
+          chunk.edit(
+            &mut self.frag_arena,
+            "".into(),
+            EditOptions { overwrite: false, store_name: false },
+          );
+        }
+      }
+      if trim_frags_start(&mut self.frag_arena, chunk.outro, &pat) {
+        return self;
+      }
+      next_chunk_idx = chunk.next;
+    }
+
+    trim_frags_start(&mut self.frag_arena, self.outro, &pat);
+    self
+  }
+
+  /// Removes trailing chars that match `pat`.
+  pub fn trim_end_matches(&mut self, pat: impl Fn(char) -> bool) -> &mut Self {
+    if trim_frags_end(&mut self.frag_arena, self.outro, &pat) {
+      return self;
+    }
+
+    let mut prev_chunk_idx = Some(self.last_chunk_idx);
+    while let Some(chunk_idx) = prev_chunk_idx {
+      let chunk = &mut self.chunks[chunk_idx];
+      if trim_frags_end(&mut self.frag_arena, chunk.outro, &pat) {
+        return self;
+      }
+      match chunk.edited_content {
+        Some(edited_idx) => {
+          let edited_content = self.frag_arena.get_mut(edited_idx);
+          trim_frag_end(edited_content, &pat);
+          if !edited_content.is_empty() {
+            return self;
+          }
+        }
+        None => {
+          let content = chunk.span.text(&self.source);
+          let kept_len = content.trim_end_matches(&pat).len();
+          if kept_len > 0 {
+            if kept_len < content.len() {
+              self.changed = true;
+              let trimmed_start = chunk.start() + kept_len;
+              // `trimmed_start` is a char boundary inside of an unedited chunk, so splitting can't
+              // fail.
+              self.split_at(trimmed_start).unwrap();
+              let trimmed_idx = self.chunk_by_start[&trimmed_start];
+              self.chunks[trimmed_idx].edit(
+                &mut self.frag_arena,
+                "".into(),
+                EditOptions { overwrite: false, store_name: false },
+              );
+            }
+            return self;
+          }
+          self.changed = true;
+          chunk.edit(
+            &mut self.frag_arena,
+            "".into(),
+            EditOptions { overwrite: false, store_name: false },
+          );
+        }
+      }
+      if trim_frags_end(&mut self.frag_arena, chunk.intro, &pat) {
+        return self;
+      }
+      prev_chunk_idx = chunk.prev;
+    }
+
+    trim_frags_end(&mut self.frag_arena, self.intro, &pat);
+    self
+  }
+}
@@ -1,4 +1,4 @@
-use crate::{chunk::EditOptions, CowStr, MagicString};
+use crate::{chunk::EditOptions, CowStr, MagicString, MagicStringError};
 
 #[derive(Debug, Default, Clone)]
 pub struct UpdateOptions {
@@ -9,6 +9,24 @@ pub struct UpdateOptions {
   pub overwrite: bool,
 }
 
+/// Decides what happens when an update or a removal partially overlaps a range that has already
+/// been replaced with non-empty content, i.e. when `start` or `end` falls inside of it.
+///
+/// Removed ranges never conflict, they are split as needed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+  /// Reject the new edit with [MagicStringError::OverlappingEdit].
+  #[default]
+  Error,
+  /// Discard the earlier edit, restoring the original content it replaced, then apply the new one.
+  LastWriterWins,
+  /// If the earlier edit contains the new one, the new edit is absorbed by it and ignored. Edits
+  /// that cross each other are rejected like [OverlapPolicy::Error].
+  ///
+  /// A new edit that contains the earlier one always replaces it, regardless of the policy.
+  Merge,
+}
+
 impl<'text> MagicString<'text> {
   /// A shorthand for `update_with(start, end, content, Default::default())`;
   pub fn update(
@@ -20,6 +38,16 @@ impl<'text> MagicString<'text> {
     self.update_with(start, end, content, Default::default())
   }
 
+  /// Fallible version of [MagicString::update].
+  pub fn try_update(
+    &mut self,
+    start: usize,
+    end: usize,
+    content: impl Into<CowStr<'text>>,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.try_update_with(start, end, content, Default::default())
+  }
+
   pub fn update_with(
     &mut self,
     start: usize,
@@ -27,47 +55,89 @@ impl<'text> MagicString<'text> {
     content: impl Into<CowStr<'text>>,
     opts: UpdateOptions,
   ) -> &mut Self {
-    self.inner_update_with(start, end, content.into(), opts, true);
-    self
+    self.try_update_with(start, end, content, opts).unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Fallible version of [MagicString::update_with].
+  pub fn try_update_with(
+    &mut self,
+    start: usize,
+    end: usize,
+    content: impl Into<CowStr<'text>>,
+    opts: UpdateOptions,
+  ) -> Result<&mut Self, MagicStringError> {
+    self.check_range(start, end)?;
+    if start == end {
+      return Err(MagicStringError::ZeroLengthRange { index: start });
+    }
+    self.inner_update_with(start, end, content.into(), opts)
   }
 
   // --- private
 
+  /// `start..end` must be a valid, non-empty range.
   pub(super) fn inner_update_with(
     &mut self,
     start: usize,
     end: usize,
     content: CowStr<'text>,
     opts: UpdateOptions,
-    panic_if_start_equal_end: bool,
-  ) -> &mut Self {
-    if panic_if_start_equal_end && start == end {
-      panic!("Cannot overwrite a zero-length range – use append_left or prepend_right instead")
+  ) -> Result<&mut Self, MagicStringError> {
+    debug_assert!(start < end);
+    self.compact_if_needed();
+    for text_index in [start, end] {
+      let Some((_, edited_span)) = self.replacement_around(text_index) else {
+        continue;
+      };
+      let overlapping_edit_err = MagicStringError::OverlappingEdit {
+        index: text_index,
+        edited_start: edited_span.start(),
+        edited_end: edited_span.end(),
+      };
+      match self.overlap_policy {
+        OverlapPolicy::Error => return Err(overlapping_edit_err),
+        OverlapPolicy::LastWriterWins => {
+          self.reset_chunks(edited_span.start(), edited_span.end(), false)
+        }
+        OverlapPolicy::Merge => {
+          if edited_span.start() <= start && end <= edited_span.end() {
+            return Ok(self);
+          }
+          return Err(overlapping_edit_err);
+        }
+      }
     }
-    assert!(start < end);
-    self.split_at(start);
-    self.split_at(end);
+    self.split_at(start)?;
+    self.split_at(end)?;
+    self.changed = true;
 
     let start_idx = self.chunk_by_start.get(&start).copied().unwrap();
     let end_idx = self.chunk_by_end.get(&end).copied().unwrap();
 
+    // The rest of the range is a part of the replacement, unless it's a removal
+    let continues_edit = !content.is_empty();
     let start_chunk = &mut self.chunks[start_idx];
     start_chunk.edit(
-      content.into(),
+      &mut self.frag_arena,
+      content,
       EditOptions { overwrite: opts.overwrite, store_name: opts.keep_original },
     );
 
-    let mut rest_chunk_idx = if start_idx != end_idx {
-      start_chunk.next.unwrap()
-    } else {
-      return self;
-    };
+    if start_idx == end_idx {
+      return Ok(self);
+    }
 
-    while rest_chunk_idx != end_idx {
+    // Walk the rest of the range in the original order, since it might have been relocated.
+    let mut rest_chunk_idx = self.chunk_by_start[&start_chunk.end()];
+    loop {
       let rest_chunk = &mut self.chunks[rest_chunk_idx];
-      rest_chunk.edit("".into(), Default::default());
-      rest_chunk_idx = rest_chunk.next.unwrap();
+      rest_chunk.edit(&mut self.frag_arena, "".into(), Default::default());
+      rest_chunk.continues_edit = continues_edit;
+      if rest_chunk_idx == end_idx {
+        break;
+      }
+      rest_chunk_idx = self.chunk_by_start[&rest_chunk.end()];
     }
-    self
+    Ok(self)
   }
 }
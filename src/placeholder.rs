@@ -0,0 +1,274 @@
+use std::{borrow::Cow, hash::Hasher};
+
+const PREFIX: &str = "!~{";
+const SUFFIX: &str = "}~";
+/// What every placeholder is replaced with by [HashPlaceholders::hash_normalized].
+const NORMALIZED: &str = "!~{}~";
+
+/// A registry of placeholders for values that are only known after the content is rendered, such as
+/// the hashed file names of chunks that import each other.
+///
+/// 1. Insert the placeholders returned by [HashPlaceholders::create] while editing.
+/// 2. Hash the content with [crate::MagicString::hash_normalized_into] or
+///    [crate::Joiner::hash_normalized_into], which doesn't depend on which placeholders are used.
+/// 3. [HashPlaceholders::resolve] the placeholders and [HashPlaceholders::substitute] them in the
+///    rendered content.
+///
+/// # Example
+/// ```rust
+/// use string_wizard::{HashPlaceholders, MagicString};
+/// let mut placeholders = HashPlaceholders::new();
+/// let placeholder = placeholders.create();
+/// let mut s = MagicString::new("import './dep.js'");
+/// s.update(10, 13, format!("dep-{placeholder}"));
+/// assert_eq!(s.to_string(), "import './dep-!~{000}~.js'");
+///
+/// placeholders.resolve(&placeholder, "5f3a");
+/// assert_eq!(placeholders.substitute(&s.to_string()), "import './dep-5f3a.js'");
+/// ```
+#[derive(Debug, Default)]
+pub struct HashPlaceholders {
+  /// The value of each placeholder, indexed by its id.
+  values: Vec<Option<String>>,
+}
+
+enum Piece<'a> {
+  Text(&'a str),
+  Placeholder { id: usize, placeholder: &'a str },
+}
+
+enum Scanned {
+  Placeholder {
+    id: usize,
+    len: usize,
+  },
+  /// The text ends before it can be decided whether it starts with a placeholder.
+  Incomplete,
+  NoMatch,
+}
+
+impl HashPlaceholders {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create a new placeholder, such as `!~{000}~`.
+  pub fn create(&mut self) -> String {
+    let id = self.values.len();
+    self.values.push(None);
+    format!("{PREFIX}{id:03}{SUFFIX}")
+  }
+
+  /// Set the value that `placeholder` will be substituted with.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `placeholder` wasn't created by this registry.
+  pub fn resolve(&mut self, placeholder: &str, value: impl Into<String>) {
+    match self.scan(placeholder) {
+      Scanned::Placeholder { id, len } if len == placeholder.len() => {
+        self.values[id] = Some(value.into());
+      }
+      _ => panic!("{placeholder:?} is not a placeholder of this registry"),
+    }
+  }
+
+  /// Replace the resolved placeholders in `content` with their values. Unresolved placeholders are
+  /// kept as they are.
+  pub fn substitute(&self, content: &str) -> String {
+    let mut ret = String::with_capacity(content.len());
+    let rest = self.split_placeholders(content, |piece| match piece {
+      Piece::Text(text) => ret.push_str(text),
+      Piece::Placeholder { id, placeholder } => {
+        ret.push_str(self.values[id].as_deref().unwrap_or(placeholder))
+      }
+    });
+    ret.push_str(&content[rest..]);
+    ret
+  }
+
+  /// Like [HashPlaceholders::substitute], but also shifts the generated columns of `source_map`,
+  /// the sourcemap of `content`, to match the substituted content.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the value of a placeholder in `content` contains a line break.
+  #[cfg(feature = "source_map")]
+  pub fn substitute_with_source_map(
+    &self,
+    content: &str,
+    source_map: &oxc_sourcemap::SourceMap,
+  ) -> (String, oxc_sourcemap::SourceMap) {
+    struct Substitution {
+      line: u32,
+      column: u32,
+      /// Length of the placeholder, which is ascii only.
+      old_len: u32,
+      /// Length of the value in utf-16.
+      new_len: u32,
+      /// How far the columns after the placeholder move, this substitution included.
+      shift: i64,
+    }
+
+    let mut ret = String::with_capacity(content.len());
+    // Sorted by position, since they are found in order.
+    let mut substitutions: Vec<Substitution> = vec![];
+    let (mut line, mut column) = (0, 0);
+    let rest = self.split_placeholders(content, |piece| match piece {
+      Piece::Text(text) => {
+        ret.push_str(text);
+        for char in text.chars() {
+          if char == '\n' {
+            line += 1;
+            column = 0;
+          } else {
+            column += char.len_utf16() as u32;
+          }
+        }
+      }
+      Piece::Placeholder { id, placeholder } => {
+        let value = self.values[id].as_deref().unwrap_or(placeholder);
+        assert!(!value.contains('\n'), "Placeholder values must not contain line breaks");
+        ret.push_str(value);
+        let old_len = placeholder.len() as u32;
+        let new_len = value.encode_utf16().count() as u32;
+        let shift_before = substitutions.last().filter(|s| s.line == line).map_or(0, |s| s.shift);
+        let shift = shift_before + i64::from(new_len) - i64::from(old_len);
+        substitutions.push(Substitution { line, column, old_len, new_len, shift });
+        column += old_len;
+      }
+    });
+    ret.push_str(&content[rest..]);
+
+    let tokens = source_map
+      .get_tokens()
+      .map(|token| {
+        let (dst_line, dst_col) = (token.get_dst_line(), token.get_dst_col());
+        // The last substitution that starts before the token on the same line
+        let idx = substitutions.partition_point(|s| (s.line, s.column) < (dst_line, dst_col));
+        let new_dst_col = match idx.checked_sub(1).map(|idx| &substitutions[idx]) {
+          Some(s) if s.line == dst_line => {
+            if dst_col >= s.column + s.old_len {
+              (i64::from(dst_col) + s.shift) as u32
+            } else {
+              // The token points into the placeholder itself
+              let shift_before = s.shift + i64::from(s.old_len) - i64::from(s.new_len);
+              let offset = (dst_col - s.column).min(s.new_len);
+              (i64::from(s.column) + shift_before) as u32 + offset
+            }
+          }
+          _ => dst_col,
+        };
+        oxc_sourcemap::Token::new(
+          dst_line,
+          new_dst_col,
+          token.get_src_line(),
+          token.get_src_col(),
+          token.get_source_id(),
+          token.get_name_id(),
+        )
+      })
+      .collect();
+
+    let source_map = oxc_sourcemap::SourceMap::new(
+      source_map.get_file().map(Into::into),
+      source_map.get_names().map(Into::into).collect(),
+      source_map.get_source_root().map(ToString::to_string),
+      source_map.get_sources().map(Into::into).collect(),
+      source_map.get_source_contents().map(|contents| contents.map(Into::into).collect()),
+      tokens,
+      None,
+    );
+    (ret, source_map)
+  }
+
+  // --- private
+
+  /// Feed the content made of `frags` into `state`, with every placeholder replaced by the same
+  /// text, so the hash doesn't depend on which placeholders are used. Placeholders split across
+  /// fragments are recognized too.
+  pub(crate) fn hash_normalized<'a>(
+    &self,
+    frags: impl Iterator<Item = &'a str>,
+    state: &mut impl Hasher,
+  ) {
+    let mut write = |piece: Piece| {
+      let bytes = match piece {
+        Piece::Text(text) => text.as_bytes(),
+        Piece::Placeholder { .. } => NORMALIZED.as_bytes(),
+      };
+      if !bytes.is_empty() {
+        state.write(bytes);
+      }
+    };
+    // The end of the previous fragments, which might be the start of a placeholder.
+    let mut carry = String::new();
+    for frag in frags {
+      let text = if carry.is_empty() {
+        Cow::Borrowed(frag)
+      } else {
+        carry.push_str(frag);
+        Cow::Owned(std::mem::take(&mut carry))
+      };
+      let rest = self.split_placeholders(&text, &mut write);
+      carry.push_str(&text[rest..]);
+    }
+    write(Piece::Text(&carry));
+  }
+
+  /// Pass the text and the placeholders of `text` to `on_piece` in order. Returns the offset of an
+  /// incomplete placeholder at the end of `text`, which is left out, or `text.len()`.
+  fn split_placeholders<'a>(&self, text: &'a str, mut on_piece: impl FnMut(Piece<'a>)) -> usize {
+    let mut text_start = 0;
+    let mut search_start = 0;
+    while let Some(found) = text[search_start..].find('!') {
+      let candidate = search_start + found;
+      match self.scan(&text[candidate..]) {
+        Scanned::Placeholder { id, len } => {
+          on_piece(Piece::Text(&text[text_start..candidate]));
+          on_piece(Piece::Placeholder { id, placeholder: &text[candidate..candidate + len] });
+          text_start = candidate + len;
+          search_start = text_start;
+        }
+        Scanned::Incomplete => {
+          on_piece(Piece::Text(&text[text_start..candidate]));
+          return candidate;
+        }
+        Scanned::NoMatch => search_start = candidate + 1,
+      }
+    }
+    on_piece(Piece::Text(&text[text_start..]));
+    text.len()
+  }
+
+  /// Check if `text` starts with a placeholder of this registry.
+  fn scan(&self, text: &str) -> Scanned {
+    fn starts_with_partial(bytes: &[u8], pat: &str) -> Option<bool> {
+      let len = bytes.len().min(pat.len());
+      (bytes[..len] == pat.as_bytes()[..len]).then_some(len == pat.len())
+    }
+
+    let bytes = text.as_bytes();
+    match starts_with_partial(bytes, PREFIX) {
+      None => return Scanned::NoMatch,
+      Some(false) => return Scanned::Incomplete,
+      Some(true) => {}
+    }
+    let digits_len = bytes[PREFIX.len()..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let suffix_start = PREFIX.len() + digits_len;
+    match starts_with_partial(&bytes[suffix_start..], SUFFIX) {
+      None => return Scanned::NoMatch,
+      Some(false) => return Scanned::Incomplete,
+      Some(true) => {}
+    }
+    let digits = &text[PREFIX.len()..suffix_start];
+    // Only the ids formatted like `create` does are placeholders
+    let is_canonical = digits.len() == 3 || (digits.len() > 3 && !digits.starts_with('0'));
+    match digits.parse::<usize>() {
+      Ok(id) if is_canonical && id < self.values.len() => {
+        Scanned::Placeholder { id, len: suffix_start + SUFFIX.len() }
+      }
+      _ => Scanned::NoMatch,
+    }
+  }
+}
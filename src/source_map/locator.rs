@@ -1,37 +1,58 @@
 #[derive(Debug)]
 pub struct Locator {
-  /// offsets are calculated based on utf-16
+  /// Byte offsets of the start of each line.
   line_offsets: Box<[usize]>,
+  /// Multi-byte chars in the source, sorted by their byte offset. Lines containing only ASCII chars
+  /// have no entries here, so locating in them doesn't need to look at the source at all.
+  multi_byte_chars: Box<[MultiByteChar]>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MultiByteChar {
+  /// Byte offset of the char in the source.
+  offset: usize,
+  /// How many bytes more than utf-16 code units have been used by multi-byte chars since the
+  /// start of the source, this char included.
+  utf8_utf16_diff: usize,
 }
 
 impl Locator {
   pub fn new(source: &str) -> Self {
-    let mut line_offsets = vec![];
-    let mut line_start_pos = 0;
-    for line in source.split('\n') {
-      line_offsets.push(line_start_pos);
-      line_start_pos += 1 + line.chars().map(|c| c.len_utf16()).sum::<usize>();
+    let mut line_offsets = vec![0];
+    let mut multi_byte_chars = vec![];
+    let mut utf8_utf16_diff = 0;
+    for (offset, char) in source.char_indices() {
+      match char {
+        '\n' => line_offsets.push(offset + 1),
+        char if !char.is_ascii() => {
+          utf8_utf16_diff += char.len_utf8() - char.len_utf16();
+          multi_byte_chars.push(MultiByteChar { offset, utf8_utf16_diff });
+        }
+        _ => {}
+      }
+    }
+    Self {
+      line_offsets: line_offsets.into_boxed_slice(),
+      multi_byte_chars: multi_byte_chars.into_boxed_slice(),
     }
-    Self { line_offsets: line_offsets.into_boxed_slice() }
   }
 
-  /// Pass the index based on utf-16 and return the [Location] based on utf-16
+  /// Pass the byte offset of a char boundary in the source and return the [Location] based on utf-16
   pub fn locate(&self, index: usize) -> Location {
-    let index = index as usize;
+    let line = self.line_offsets.partition_point(|&line_offset| line_offset <= index) - 1;
+    let line_offset = self.line_offsets[line];
+    let column = self.to_utf16_index(index) - self.to_utf16_index(line_offset);
+    Location { line, column }
+  }
 
-    let mut left_cursor = 0;
-    let mut right_cursor = self.line_offsets.len();
-    while left_cursor < right_cursor {
-      let mid = (left_cursor + right_cursor) >> 1;
-      if index < self.line_offsets[mid] {
-        right_cursor = mid;
-      } else {
-        left_cursor = mid + 1;
-      }
+  /// Convert a byte offset of the source to the corresponding utf-16 offset.
+  fn to_utf16_index(&self, index: usize) -> usize {
+    let multi_byte_chars_before =
+      self.multi_byte_chars.partition_point(|multi_byte_char| multi_byte_char.offset < index);
+    match multi_byte_chars_before.checked_sub(1) {
+      Some(last) => index - self.multi_byte_chars[last].utf8_utf16_diff,
+      None => index,
     }
-    let line = left_cursor - 1;
-    let column = index - self.line_offsets[line];
-    Location { line: line.try_into().unwrap(), column: column.try_into().unwrap() }
   }
 }
 
@@ -69,11 +90,25 @@ fn special_chars() {
   let source = "ß💣\n💣ß";
   let locator = Locator::new(source);
   assert_eq!(locator.line_offsets[0], 0);
-  assert_eq!(locator.line_offsets[1], 4);
+  assert_eq!(locator.line_offsets[1], 7);
 
   assert_eq!(locator.locate(0), Location { line: 0, column: 0 });
+  assert_eq!(locator.locate(2), Location { line: 0, column: 1 });
+  assert_eq!(locator.locate(6), Location { line: 0, column: 3 });
+  assert_eq!(locator.locate(7), Location { line: 1, column: 0 });
+  assert_eq!(locator.locate(11), Location { line: 1, column: 2 });
+  assert_eq!(locator.locate(13), Location { line: 1, column: 3 });
+}
+
+#[test]
+fn cjk_after_ascii_lines() {
+  let source = "abc\n中文abc\nxyz";
+  let locator = Locator::new(source);
   assert_eq!(locator.locate(4), Location { line: 1, column: 0 });
-  assert_eq!(locator.locate(6), Location { line: 1, column: 2 });
+  assert_eq!(locator.locate(10), Location { line: 1, column: 2 });
+  assert_eq!(locator.locate(13), Location { line: 1, column: 5 });
+  assert_eq!(locator.locate(14), Location { line: 2, column: 0 });
+  assert_eq!(locator.locate(16), Location { line: 2, column: 2 });
 }
 
 #[test]
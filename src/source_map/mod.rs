@@ -1,2 +1,3 @@
 pub mod locator;
+pub mod remapping;
 pub mod sourcemap_builder;
@@ -0,0 +1,54 @@
+use oxc_sourcemap::SourceMap;
+
+use crate::SourceMapOptions;
+
+use super::sourcemap_builder::finish_source_map;
+
+/// Trace the mappings of `source_map` through `input_map`, which maps the single source of
+/// `source_map` back to its own sources. The result maps straight to the sources of `input_map`.
+///
+/// - A name of `input_map` takes precedence over the one of `source_map` at the same position.
+/// - Mappings to a position that has no mapping on the same line of `input_map` are dropped.
+/// - Sources of `input_map` without content get an empty content if `opts.include_content` is set.
+pub fn remap(source_map: &SourceMap, input_map: &SourceMap, opts: &SourceMapOptions) -> SourceMap {
+  let lookup_table = input_map.generate_lookup_table();
+  let mut builder = oxc_sourcemap::SourceMapBuilder::default();
+  // Ids of the sources of `input_map` in the new sourcemap, sources are added once they are used.
+  let mut source_ids = vec![None; input_map.get_sources().count()];
+
+  for token in source_map.get_tokens() {
+    let Some(input_token) =
+      input_map.lookup_token(&lookup_table, token.get_src_line(), token.get_src_col())
+    else {
+      continue;
+    };
+    // `lookup_token` falls back to the mappings of previous lines, which don't cover this position.
+    if input_token.get_dst_line() != token.get_src_line() {
+      continue;
+    }
+    let Some(input_source_id) = input_token.get_source_id() else {
+      continue;
+    };
+    let source_id = *source_ids[input_source_id as usize].get_or_insert_with(|| {
+      builder.add_source_and_content(
+        input_map.get_source(input_source_id).unwrap_or_default(),
+        input_map.get_source_content(input_source_id).unwrap_or_default(),
+      )
+    });
+    let name = input_token
+      .get_name_id()
+      .and_then(|id| input_map.get_name(id))
+      .or_else(|| token.get_name_id().and_then(|id| source_map.get_name(id)));
+    let name_id = name.map(|name| builder.add_name(name));
+    builder.add_token(
+      token.get_dst_line(),
+      token.get_dst_col(),
+      input_token.get_src_line(),
+      input_token.get_src_col(),
+      Some(source_id),
+      name_id,
+    );
+  }
+
+  finish_source_map(builder, opts)
+}
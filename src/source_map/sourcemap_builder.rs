@@ -1,9 +1,40 @@
-use crate::chunk::Chunk;
+use crate::{chunk::Chunk, Hires, SourceMapOptions};
 
-use super::locator::Locator;
+use super::locator::{Location, Locator};
+
+/// Turn `builder` into a sourcemap, applying the `file`, `source_root` and `include_content` of
+/// `opts`.
+pub fn finish_source_map(
+  mut builder: oxc_sourcemap::SourceMapBuilder,
+  opts: &SourceMapOptions,
+) -> oxc_sourcemap::SourceMap {
+  if let Some(file) = &opts.file {
+    builder.set_file(file);
+  }
+  let source_map = builder.into_sourcemap();
+  if opts.include_content && opts.source_root.is_none() {
+    return source_map;
+  }
+
+  // `oxc_sourcemap::SourceMapBuilder` always embeds the sources content and can't set the source
+  // root, so rebuild the sourcemap from its parts.
+  let source_contents = opts
+    .include_content
+    .then(|| source_map.get_source_contents().map(|contents| contents.map(Into::into).collect()))
+    .flatten();
+  oxc_sourcemap::SourceMap::new(
+    source_map.get_file().map(Into::into),
+    source_map.get_names().map(Into::into).collect(),
+    opts.source_root.clone(),
+    source_map.get_sources().map(Into::into).collect(),
+    source_contents,
+    source_map.get_tokens().cloned().collect(),
+    None,
+  )
+}
 
 pub struct SourcemapBuilder {
-  hires: bool,
+  hires: Hires,
   generated_code_line: usize,
   /// `generated_code_column` is calculated based on utf-16.
   generated_code_column: usize,
@@ -12,7 +43,7 @@ pub struct SourcemapBuilder {
 }
 
 impl SourcemapBuilder {
-  pub fn new(hires: bool) -> Self {
+  pub fn new(hires: Hires) -> Self {
     Self {
       hires,
       generated_code_line: 0,
@@ -22,22 +53,36 @@ impl SourcemapBuilder {
     }
   }
 
-  pub fn into_source_map(self) -> oxc_sourcemap::SourceMap {
-    self.source_map_builder.into_sourcemap()
+  /// Finish the sourcemap, applying the `file`, `source_root` and `include_content` of `opts`.
+  pub fn into_source_map(self, opts: &SourceMapOptions) -> oxc_sourcemap::SourceMap {
+    finish_source_map(self.source_map_builder, opts)
   }
 
   pub fn set_source_and_content(&mut self, id: &str, content: &str) {
     self.source_id = self.source_map_builder.set_source_and_content(id, content);
   }
 
-  pub fn add_chunk(&mut self, chunk: &Chunk, locator: &Locator, source: &str, name: Option<&str>) {
+  /// Like [SourcemapBuilder::set_source_and_content], but reuses the existing source if `id` was added before.
+  pub fn add_source_and_content(&mut self, id: &str, content: &str) {
+    self.source_id = self.source_map_builder.add_source_and_content(id, content);
+  }
+
+  /// `edited_content` is what `chunk` was edited to, if any.
+  pub fn add_chunk(
+    &mut self,
+    chunk: &Chunk,
+    edited_content: Option<&str>,
+    locator: &Locator,
+    source: &str,
+    name: Option<&str>,
+  ) {
     let name_id = if chunk.keep_in_mappings {
       name.map(|name| self.source_map_builder.add_name(name))
     } else {
       None
     };
-    let mut loc = locator.locate(chunk.start());
-    if let Some(edited_content) = &chunk.edited_content {
+    let loc = locator.locate(chunk.start());
+    if let Some(edited_content) = edited_content {
       if !edited_content.is_empty() {
         self.source_map_builder.add_token(
           self.generated_code_line as u32,
@@ -50,32 +95,47 @@ impl SourcemapBuilder {
       }
       self.advance(edited_content);
     } else {
-      let chunk_content = chunk.span.text(source);
-      let mut new_line = true;
-      for char in chunk_content.chars() {
-        // TODO support hires boundary
-        if new_line || self.hires {
-          self.source_map_builder.add_token(
-            self.generated_code_line as u32,
-            self.generated_code_column as u32,
-            loc.line as u32,
-            loc.column as u32,
-            Some(self.source_id),
-            name_id,
-          );
+      self.add_original(chunk.span.text(source), loc);
+    }
+  }
+
+  /// Emit the mappings of `content`, which is unedited original content starting at `loc`.
+  pub fn add_original(&mut self, content: &str, mut loc: Location) {
+    let mut new_line = true;
+    let mut in_word = false;
+    for char in content.chars() {
+      let is_token = match self.hires {
+        Hires::Off => new_line,
+        Hires::Boundary => {
+          let is_word_char = char.is_ascii_alphanumeric() || char == '_';
+          // Only the first char of a word gets a token, and line breaks don't get one
+          let is_token = char != '\n' && (new_line || !is_word_char || !in_word);
+          in_word = is_word_char;
+          is_token
+        }
+        Hires::Char => true,
+      };
+      if is_token {
+        self.source_map_builder.add_token(
+          self.generated_code_line as u32,
+          self.generated_code_column as u32,
+          loc.line as u32,
+          loc.column as u32,
+          Some(self.source_id),
+          None,
+        );
+      }
+      match char {
+        '\n' => {
+          loc.bump_line();
+          self.bump_line();
+          new_line = true;
         }
-        match char {
-          '\n' => {
-            loc.bump_line();
-            self.bump_line();
-            new_line = true;
-          }
-          _ => {
-            let char_utf16_len = char.len_utf16();
-            loc.column += char_utf16_len;
-            self.generated_code_column += char_utf16_len;
-            new_line = false;
-          }
+        _ => {
+          let char_utf16_len = char.len_utf16();
+          loc.column += char_utf16_len;
+          self.generated_code_column += char_utf16_len;
+          new_line = false;
         }
       }
     }
@@ -1,4 +1,5 @@
-#[derive(Debug, Default, Clone, Copy)]
+/// The byte range `start..end` of the original source.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Span(pub usize, pub usize);
 
 impl Span {
@@ -11,8 +12,6 @@ impl Span {
   }
 
   pub fn text<'s>(&self, source: &'s str) -> &'s str {
-    // This crate doesn't support usize which is u16 on 16-bit platforms.
-    // So, we can safely cast usize/u32 to usize.
-    &source[self.start() as usize..self.end() as usize]
+    &source[self.start()..self.end()]
   }
 }
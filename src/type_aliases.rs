@@ -2,4 +2,4 @@ use index_vec::IndexVec;
 
 use crate::chunk::{Chunk, ChunkIdx};
 
-pub type IndexChunks<'text> = IndexVec<ChunkIdx, Chunk<'text>>;
+pub type IndexChunks = IndexVec<ChunkIdx, Chunk>;
@@ -0,0 +1,77 @@
+use std::hash::{DefaultHasher, Hasher};
+
+use string_wizard::{HashPlaceholders, Joiner, MagicString};
+
+fn hash_normalized(s: &MagicString, placeholders: &HashPlaceholders) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  s.hash_normalized_into(placeholders, &mut hasher);
+  hasher.finish()
+}
+
+#[test]
+fn create_and_substitute() {
+  let mut placeholders = HashPlaceholders::new();
+  let a = placeholders.create();
+  let b = placeholders.create();
+  assert_eq!((a.as_str(), b.as_str()), ("!~{000}~", "!~{001}~"));
+
+  let content = format!("import '{a}'; import '{b}'; import '{a}';");
+  placeholders.resolve(&a, "a-1f2e.js");
+  // `b` is unresolved and the rest aren't placeholders of the registry
+  assert_eq!(
+    placeholders.substitute(&format!("{content} !~{{002}}~ !~{{00}}~ !~{{0001}}~ !~{{000}} !")),
+    format!("import 'a-1f2e.js'; import '{b}'; import 'a-1f2e.js'; !~{{002}}~ !~{{00}}~ !~{{0001}}~ !~{{000}} !")
+  );
+}
+
+#[test]
+#[should_panic]
+fn resolve_unknown_placeholder() {
+  let mut placeholders = HashPlaceholders::new();
+  placeholders.create();
+  placeholders.resolve("!~{001}~", "value");
+}
+
+#[test]
+fn hash_ignores_which_placeholders_are_used() {
+  let mut placeholders = HashPlaceholders::new();
+  let a = placeholders.create();
+  let b = placeholders.create();
+
+  let mut s1 = MagicString::new("import './dep.js'");
+  s1.update(8, 16, format!("./{a}.js"));
+  let mut s2 = MagicString::new("import './dep.js'");
+  s2.update(8, 16, format!("./{b}.js"));
+  assert_eq!(hash_normalized(&s1, &placeholders), hash_normalized(&s2, &placeholders));
+
+  // Placeholders split across fragments are recognized too
+  let mut s3 = MagicString::new("import './dep.js'");
+  s3.update(8, 16, "./!~{0").append_left(16, "01").append_left(16, "}~.js");
+  assert_eq!(s3.to_string(), s2.to_string());
+  assert_eq!(hash_normalized(&s3, &placeholders), hash_normalized(&s2, &placeholders));
+
+  let mut expected = DefaultHasher::new();
+  expected.write(b"import './!~{}~.js'");
+  assert_eq!(hash_normalized(&s1, &placeholders), expected.finish());
+
+  let mut s4 = MagicString::new("import './dep.js';");
+  s4.update(8, 16, format!("./{a}.js"));
+  assert_ne!(hash_normalized(&s4, &placeholders), hash_normalized(&s1, &placeholders));
+}
+
+#[test]
+fn joiner_hash_ignores_which_placeholders_are_used() {
+  let mut placeholders = HashPlaceholders::new();
+  let a = placeholders.create();
+  let b = placeholders.create();
+
+  let hash = |placeholder: &str| {
+    let mut j = Joiner::new();
+    j.append_raw("import './").append_raw(placeholder.to_string()).append_raw(".js'");
+    let mut hasher = DefaultHasher::new();
+    j.hash_normalized_into(&placeholders, &mut hasher);
+    hasher.finish()
+  };
+  assert_eq!(hash(&a), hash(&b));
+  assert_ne!(hash(&a), hash("dep"));
+}
@@ -21,3 +21,51 @@ fn separator() {
   j.append_raw("123");
   assert_eq!(j.join(), "123,123,123");
 }
+
+#[test]
+fn is_empty() {
+  let mut j = Joiner::with_options(JoinerOptions { separator: Some(",".to_string()) });
+  assert!(j.is_empty());
+  j.append_raw("");
+  assert!(j.is_empty());
+  j.append_raw("");
+  assert!(!j.is_empty());
+  assert_eq!(j.join(), ",");
+
+  let mut j = Joiner::new();
+  let mut s = MagicString::new("abc");
+  s.remove(0, 3);
+  j.append(s).append_raw("");
+  assert!(j.is_empty());
+}
+
+#[test]
+fn write() {
+  let mut j = Joiner::with_options(JoinerOptions { separator: Some("\n".to_string()) });
+  let mut s = MagicString::new("abc");
+  s.update(1, 2, "B");
+  j.append(s).append_raw("def");
+
+  let mut bytes = vec![];
+  j.write_to(&mut bytes).unwrap();
+  assert_eq!(bytes, b"aBc\ndef");
+  let mut string = String::new();
+  j.write_fmt_to(&mut string).unwrap();
+  assert_eq!(string, j.join());
+}
+
+#[test]
+fn hash_into() {
+  use std::hash::{DefaultHasher, Hasher};
+
+  let mut j = Joiner::with_options(JoinerOptions { separator: Some("\n".to_string()) });
+  let mut s = MagicString::new("abc");
+  s.update(1, 2, "B");
+  j.append(s).append_raw("def").append_raw("");
+
+  let mut hasher = DefaultHasher::new();
+  j.hash_into(&mut hasher);
+  let mut expected = DefaultHasher::new();
+  expected.write(j.join().as_bytes());
+  assert_eq!(hasher.finish(), expected.finish());
+}
@@ -0,0 +1,79 @@
+use string_wizard::{
+  Hires, Joiner, JoinerOptions, MagicString, MagicStringOptions, SourceMapOptions,
+};
+
+fn with_filename<'s>(source: &'s str, filename: &str) -> MagicString<'s> {
+  MagicString::with_options(
+    source,
+    MagicStringOptions { filename: Some(filename.to_string()), ..Default::default() },
+  )
+}
+
+#[test]
+fn basic() {
+  let mut a = with_filename("const a = 1;\nconsole.log(a);", "a.js");
+  a.update(6, 7, "aa");
+  let b = with_filename("export const b = 2;", "b.js");
+
+  let mut j = Joiner::with_options(JoinerOptions { separator: Some("\n".to_string()) });
+  j.append(a).append(b);
+
+  assert_eq!(j.join(), "const aa = 1;\nconsole.log(a);\nexport const b = 2;");
+  let sm = j.source_map(SourceMapOptions { include_content: true, ..Default::default() });
+  assert_eq!(
+    sm.to_json_string(),
+    "{\"version\":3,\"names\":[],\"sources\":[\"a.js\",\"b.js\"],\"sourcesContent\":[\"const a = 1;\\nconsole.log(a);\",\"export const b = 2;\"],\"mappings\":\"AAAA,MAAM,EAAC;AACP;ACDA\"}"
+  );
+}
+
+#[test]
+fn offsets_across_separators_and_raw_sources() {
+  let mut j = Joiner::with_options(JoinerOptions { separator: Some(";".to_string()) });
+  j.append_raw("/* banner */");
+  let mut a = with_filename("abc", "a.js");
+  a.prepend("\n");
+  j.append(a);
+  j.append(with_filename("def", "b.js"));
+
+  assert_eq!(j.join(), "/* banner */;\nabc;def");
+  let sm = j.source_map(SourceMapOptions { hires: Hires::Char, ..Default::default() });
+  let tokens = sm
+    .get_tokens()
+    .map(|token| {
+      let source = token.get_source_id().and_then(|id| sm.get_source(id));
+      (token.get_dst_line(), token.get_dst_col(), source, token.get_src_col())
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(
+    tokens,
+    vec![
+      (1, 0, Some("a.js"), 0),
+      (1, 1, Some("a.js"), 1),
+      (1, 2, Some("a.js"), 2),
+      (1, 4, Some("b.js"), 0),
+      (1, 5, Some("b.js"), 1),
+      (1, 6, Some("b.js"), 2),
+    ]
+  );
+}
+
+#[test]
+fn shares_sources_with_the_same_filename() {
+  let mut j = Joiner::new();
+  j.append(with_filename("a", "a.js")).append(with_filename("a", "a.js"));
+  let sm = j.source_map(SourceMapOptions::default());
+  assert_eq!(sm.get_sources().collect::<Vec<_>>(), vec!["a.js"]);
+  assert_eq!(sm.get_tokens().filter_map(|token| token.get_source_id()).collect::<Vec<_>>(), [0, 0]);
+}
+
+#[test]
+fn omits_sources_content_by_default() {
+  let mut j = Joiner::new();
+  j.append(with_filename("a", "a.js")).append(with_filename("b", "b.js"));
+  let sm =
+    j.source_map(SourceMapOptions { source_root: Some("/src".to_string()), ..Default::default() });
+  assert_eq!(
+    sm.to_json_string(),
+    "{\"version\":3,\"sourceRoot\":\"/src\",\"names\":[],\"sources\":[\"a.js\",\"b.js\"],\"mappings\":\"AAAA,CCAA\"}"
+  );
+}
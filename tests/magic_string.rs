@@ -1,8 +1,18 @@
 use std::borrow::Cow;
 
+use string_wizard::Bias;
+use string_wizard::Edit;
 use string_wizard::IndentOptions;
+use string_wizard::LineColumn;
 use string_wizard::MagicString;
+use string_wizard::MagicStringError;
 use string_wizard::MagicStringOptions;
+use string_wizard::OriginalPosition;
+use string_wizard::OverlapPolicy;
+use string_wizard::ResetOptions;
+use string_wizard::Segment;
+use string_wizard::SegmentKind;
+use string_wizard::Span;
 use string_wizard::UpdateOptions;
 
 trait MagicStringExt<'text> {
@@ -35,8 +45,10 @@ mod options {
   use super::*;
   #[test]
   fn stores_source_file_information() {
-    let s =
-      MagicString::with_options("abc", MagicStringOptions { filename: Some("foo.js".to_string()) });
+    let s = MagicString::with_options(
+      "abc",
+      MagicStringOptions { filename: Some("foo.js".to_string()), ..Default::default() },
+    );
     assert_eq!(s.filename, Some("foo.js".to_string()))
   }
 }
@@ -134,15 +146,113 @@ mod overwrite {
     assert_eq!(s.to_string(), "abcdeFGHijkl");
   }
 
-  // #[test]
-  // fn should_throw_an_error_if_overlapping_replacements_are_attempted() {
-  //     let mut s = MagicString::new("abcdefghijkl");
-  //     s.overwrite(7, 11, "xx");
-  //     assert!(std::panic::catch_unwind(|| {
-  //         s.clone().overwrite(8, 12, "yy");
-  //     })
-  //     .is_err())
-  // }
+  #[test]
+  fn should_throw_an_error_if_overlapping_replacements_are_attempted() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.overwrite(7, 11, "xx");
+    assert!(std::panic::catch_unwind(|| {
+      s.clone().overwrite(8, 12, "yy");
+    })
+    .is_err());
+    assert_eq!(s.to_string(), "abcdefgxxl");
+    s.overwrite(6, 12, "yes");
+    assert_eq!(s.to_string(), "abcdefyes");
+    s.overwrite(1, 12, "yes");
+    assert_eq!(s.to_string(), "ayes");
+  }
+
+  #[test]
+  fn reports_overlapping_replacements_without_splitting() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.overwrite(7, 11, "xx");
+    assert_eq!(
+      s.try_update(8, 12, "yy").unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 8, edited_start: 7, edited_end: 11 }
+    );
+    assert_eq!(
+      s.try_remove(3, 9).unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 9, edited_start: 7, edited_end: 11 }
+    );
+    assert_eq!(s.to_string(), "abcdefgxxl");
+  }
+
+  #[test]
+  fn last_writer_wins() {
+    let mut s = MagicString::with_options(
+      "abcdefghijkl",
+      MagicStringOptions { overlap_policy: OverlapPolicy::LastWriterWins, ..Default::default() },
+    );
+    s.overwrite(7, 11, "xx");
+    s.overwrite(8, 12, "yy");
+    assert_eq!(s.to_string(), "abcdefghyy");
+    s.overwrite(2, 4, "zz").remove(3, 9);
+    assert_eq!(s.to_string(), "abcjkl");
+  }
+
+  #[test]
+  fn last_writer_wins_over_an_edit_split_by_an_insertion() {
+    let mut s = MagicString::with_options(
+      "abcdefghijkl",
+      MagicStringOptions { overlap_policy: OverlapPolicy::LastWriterWins, ..Default::default() },
+    );
+    s.update(2, 8, "X").append_left(5, "!");
+    // The whole edit of 2..8 is undone
+    s.update(3, 6, "Y");
+    assert_eq!(s.to_string(), "abcY!ghijkl");
+  }
+
+  #[test]
+  fn merge_into_the_containing_edit() {
+    let mut s = MagicString::with_options(
+      "abcdefghijkl",
+      MagicStringOptions { overlap_policy: OverlapPolicy::Merge, ..Default::default() },
+    );
+    s.overwrite(3, 9, "xx");
+    // Contained by the edit of 3..9
+    s.overwrite(4, 6, "yy").overwrite(3, 5, "yy").remove(7, 9);
+    assert_eq!(s.to_string(), "abcxxjkl");
+    // Crossing the edit of 3..9
+    assert_eq!(
+      s.try_update(8, 12, "yy").unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 8, edited_start: 3, edited_end: 9 }
+    );
+    // Containing the edit of 3..9
+    s.overwrite(2, 10, "zz");
+    assert_eq!(s.to_string(), "abzzkl");
+  }
+
+  #[test]
+  fn error_on_overlapping_an_edit_split_by_an_insertion() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(2, 8, "X").append_left(5, "!");
+    assert_eq!(
+      s.try_update(6, 10, "Y").unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 6, edited_start: 2, edited_end: 8 }
+    );
+    assert_eq!(
+      s.try_update(0, 5, "Y").unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 5, edited_start: 2, edited_end: 8 }
+    );
+    assert_eq!(s.to_string(), "abX!ijkl");
+  }
+
+  #[test]
+  fn merge_into_an_edit_split_by_an_insertion() {
+    let mut s = MagicString::with_options(
+      "abcdefghijkl",
+      MagicStringOptions { overlap_policy: OverlapPolicy::Merge, ..Default::default() },
+    );
+    s.update(2, 8, "X").append_left(5, "!");
+    // Contained by the edit of 2..8
+    s.update(6, 7, "Y");
+    assert_eq!(s.to_string(), "abX!ijkl");
+    // Crossing the edit of 2..8
+    assert_eq!(
+      s.try_update(6, 10, "Y").unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 6, edited_start: 2, edited_end: 8 }
+    );
+    assert_eq!(s.to_string(), "abX!ijkl");
+  }
 }
 
 mod relocate {
@@ -188,6 +298,15 @@ mod relocate {
     assert_eq!(s.to_string(), "abcdefXjklYghi");
   }
 
+  #[test]
+  fn ignores_redundant_move_before_a_chunk() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.relocate(3, 6, 9);
+    assert_eq!(s.to_string(), "abcghidefjkl");
+    s.relocate(3, 6, 9).relocate(4, 6, 9);
+    assert_eq!(s.to_string(), "abcghidefjkl");
+  }
+
   #[test]
   fn moves_content_to_the_middle() {
     let mut s = MagicString::new("abcdefghijkl");
@@ -262,6 +381,433 @@ mod relocate {
   }
 }
 
+mod split_edited {
+  use super::*;
+
+  #[test]
+  fn inserts_into_removed_ranges() {
+    let mut s = MagicString::new("0123456789abc");
+    s.remove(0, 10);
+    s.append_left(5, "A").prepend_right(5, "B");
+    assert_eq!(s.to_string(), "ABabc");
+    s.relocate(7, 11, 0);
+    assert_eq!(s.to_string(), "aABbc");
+  }
+
+  #[test]
+  fn updates_inside_removed_ranges() {
+    let mut s = MagicString::new("0123456789abc");
+    s.remove(0, 10);
+    s.update(3, 6, "X");
+    assert_eq!(s.to_string(), "Xabc");
+    s.update(6, 8, "Y");
+    assert_eq!(s.to_string(), "XYabc");
+  }
+
+  #[test]
+  fn removes_overlapping_ranges() {
+    let mut s1 = MagicString::new("abcdefghijkl");
+    s1.remove(3, 7).remove(5, 9);
+    assert_eq!(s1.to_string(), "abcjkl");
+
+    let mut s2 = MagicString::new("abcdefghijkl");
+    s2.remove(3, 7).remove(1, 5);
+    assert_eq!(s2.to_string(), "ahijkl");
+  }
+
+  #[test]
+  fn keeps_the_replacement_on_the_first_half() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(3, 9, "XYZ");
+    s.append_left(6, "<").prepend_right(6, ">");
+    assert_eq!(s.to_string(), "abcXYZ<>jkl");
+    // The second half is still a part of the replacement
+    assert_eq!(
+      s.try_remove(6, 8).unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 6, edited_start: 3, edited_end: 9 }
+    );
+  }
+}
+
+mod slice {
+  use super::*;
+
+  #[test]
+  fn should_return_the_generated_content_between_the_specified_original_characters() {
+    let mut s = MagicString::new("abcdefghijkl");
+    assert_eq!(s.slice(3, 9).unwrap(), "defghi");
+    s.overwrite(4, 8, "XX");
+    assert_eq!(s.slice(3, 9).unwrap(), "dXXi");
+    s.overwrite(2, 10, "ZZ");
+    assert_eq!(s.slice(1, 11).unwrap(), "bZZk");
+    assert_eq!(s.slice(2, 10).unwrap(), "ZZ");
+    assert_eq!(
+      s.slice(3, 9).unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 3, edited_start: 2, edited_end: 10 }
+    );
+  }
+
+  #[test]
+  fn error_inside_an_edit_split_by_an_insertion() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(2, 8, "X").append_left(5, "!");
+    assert_eq!(s.slice(2, 8).unwrap(), "X!");
+    assert_eq!(
+      s.slice(6, 10).unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 6, edited_start: 2, edited_end: 8 }
+    );
+  }
+
+  #[test]
+  fn includes_inserted_characters_respecting_insertion_direction() {
+    let mut s = MagicString::new("abefij");
+    s.prepend_right(2, "cd");
+    s.append_left(4, "gh");
+    assert_eq!(s.slice(0, 6).unwrap(), "abcdefghij");
+    assert_eq!(s.slice(1, 5).unwrap(), "bcdefghi");
+    assert_eq!(s.slice(2, 4).unwrap(), "cdefgh");
+    assert_eq!(s.slice(3, 4).unwrap(), "fgh");
+    assert_eq!(s.slice(0, 2).unwrap(), "ab");
+    assert_eq!(s.slice(0, 3).unwrap(), "abcde");
+    assert_eq!(s.slice(4, 6).unwrap(), "ij");
+    assert_eq!(s.slice(3, 6).unwrap(), "fghij");
+  }
+
+  #[test]
+  fn supports_characters_moved_outward() {
+    let mut s = MagicString::new("abcdEFghIJklmn");
+    s.relocate(4, 6, 2);
+    s.relocate(8, 10, 12);
+    assert_eq!(s.to_string(), "abEFcdghklIJmn");
+    assert_eq!(s.slice(1, 13).unwrap(), "bEFcdghklIJm");
+    assert_eq!(s.slice(2, 12).unwrap(), "cdghkl");
+    assert_eq!(s.slice(3, 11).unwrap(), "dghk");
+    assert_eq!(s.slice(4, 10).unwrap(), "EFcdghklIJ");
+    assert_eq!(s.slice(5, 9).unwrap(), "FcdghklI");
+    assert_eq!(s.slice(6, 8).unwrap(), "gh");
+  }
+
+  #[test]
+  fn supports_characters_moved_opposing() {
+    let mut s = MagicString::new("abCDefghIJkl");
+    s.relocate(2, 4, 8);
+    s.relocate(8, 10, 4);
+    assert_eq!(s.to_string(), "abIJefghCDkl");
+    assert_eq!(s.slice(1, 11).unwrap(), "bIJefghCDk");
+    assert_eq!(s.slice(4, 8).unwrap(), "efgh");
+    assert_eq!(s.slice(0, 3).unwrap(), "abIJefghC");
+    assert_eq!(s.slice(0, 9).unwrap(), "abI");
+    assert_eq!(s.slice(3, 12).unwrap(), "Dkl");
+    assert_eq!(s.slice(2, 10).unwrap_err(), MagicStringError::RelocatedRange { start: 2, end: 10 });
+  }
+
+  #[test]
+  fn treats_removed_content_as_empty() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.remove(2, 10);
+    assert_eq!(s.slice(4, 6).unwrap(), "");
+    assert_eq!(s.slice(1, 11).unwrap(), "bk");
+  }
+}
+
+mod snip {
+  use super::*;
+
+  #[test]
+  fn should_return_a_clone_with_content_outside_start_and_end_removed() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.overwrite(6, 9, "GHI");
+    let snippet = s.snip(3, 9);
+    assert_eq!(snippet.to_string(), "defGHI");
+    assert_eq!(s.to_string(), "abcdefGHIjkl");
+  }
+
+  #[test]
+  fn should_snip_from_the_start() {
+    let s = MagicString::new("abcdefghijkl");
+    assert_eq!(s.snip(0, 6).to_string(), "abcdef");
+  }
+
+  #[test]
+  fn should_snip_from_the_end() {
+    let s = MagicString::new("abcdefghijkl");
+    assert_eq!(s.snip(6, 12).to_string(), "ghijkl");
+  }
+
+  #[test]
+  fn keeps_inserts_inside_the_range() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.prepend(">").append("<");
+    s.append_left(3, "[").prepend_right(3, "(").append_left(6, ")").prepend_right(6, "]");
+    // Content relocated into the range is still outside of it in the original source
+    s.relocate(9, 12, 4);
+    assert_eq!(s.to_string(), ">abc[(djklef)]ghi<");
+    assert_eq!(s.snip(3, 6).to_string(), "(def)");
+  }
+
+  #[test]
+  fn reports_overlapping_edits() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.overwrite(2, 4, "CD");
+    assert_eq!(
+      s.try_snip(3, 6).unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 3, edited_start: 2, edited_end: 4 }
+    );
+  }
+}
+
+mod trim {
+  use super::*;
+
+  #[test]
+  fn should_trim_original_content() {
+    let mut s = MagicString::new("   abcdefghijkl   ");
+    s.trim();
+    assert_eq!(s.to_string(), "abcdefghijkl");
+  }
+
+  #[test]
+  fn should_trim_replaced_content() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.overwrite(0, 3, "   ").overwrite(9, 12, "   ").trim();
+    assert_eq!(s.to_string(), "defghi");
+  }
+
+  #[test]
+  fn should_trim_original_content_before_replaced_content() {
+    let mut s = MagicString::new("abc   def");
+    s.remove(6, 9);
+    assert_eq!(s.to_string(), "abc   ");
+    s.trim();
+    assert_eq!(s.to_string(), "abc");
+  }
+
+  #[test]
+  fn should_trim_original_content_after_replaced_content() {
+    let mut s = MagicString::new("abc   def");
+    s.remove(0, 3);
+    assert_eq!(s.to_string(), "   def");
+    s.trim();
+    assert_eq!(s.to_string(), "def");
+  }
+
+  #[test]
+  fn should_trim_original_content_before_and_after_replaced_content() {
+    let mut s = MagicString::new("abc   def   ghi");
+    s.remove(0, 3);
+    s.remove(12, 15);
+    assert_eq!(s.to_string(), "   def   ");
+    s.trim();
+    assert_eq!(s.to_string(), "def");
+  }
+
+  #[test]
+  fn should_trim_appended_and_prepended_content() {
+    let mut s = MagicString::new(" abcdefghijkl ");
+    s.prepend("  ").append("  ").trim();
+    assert_eq!(s.to_string(), "abcdefghijkl");
+  }
+
+  #[test]
+  fn should_trim_empty_string() {
+    let mut s = MagicString::new("   ");
+    assert_eq!(s.trim().to_string(), "");
+  }
+
+  #[test]
+  fn should_support_trimming_chunks_with_intro_and_outro() {
+    let mut s = MagicString::new("    \n");
+    s.append_right(4, "test");
+    assert_eq!(s.trim().to_string(), "test");
+  }
+
+  #[test]
+  fn trims_start_and_end_separately() {
+    let mut s = MagicString::new("  abc  ");
+    s.prepend(" \n");
+    assert_eq!(s.clone().trim_start().to_string(), "abc  ");
+    assert_eq!(s.trim_end().to_string(), " \n  abc");
+  }
+
+  #[test]
+  fn trims_lines() {
+    let mut s = MagicString::new("\n\n   abcdefghijkl   \r\n\n");
+    s.trim_lines();
+    assert_eq!(s.to_string(), "   abcdefghijkl   ");
+  }
+
+  #[test]
+  fn trims_with_a_custom_predicate() {
+    let mut s = MagicString::new("--abc--");
+    s.append("-").trim_matches(|c| c == '-');
+    assert_eq!(s.to_string(), "abc");
+  }
+}
+
+mod replace {
+  use super::*;
+
+  #[test]
+  fn replaces_the_first_occurrence() {
+    let mut s = MagicString::new("abcabc");
+    s.replace("bc", "BC");
+    assert_eq!(s.to_string(), "aBCabc");
+    s.replace("xyz", "XYZ");
+    assert_eq!(s.to_string(), "aBCabc");
+  }
+
+  #[test]
+  fn replaces_all_occurrences() {
+    let mut s = MagicString::new("abcabc");
+    s.replace_all("bc", "BC");
+    assert_eq!(s.to_string(), "aBCaBC");
+  }
+
+  #[test]
+  fn searches_the_original_source() {
+    let mut s = MagicString::new("abcabc");
+    s.prepend("bc").update(0, 1, "b").replace_all("bc", "_");
+    assert_eq!(s.to_string(), "bcb_a_");
+  }
+
+  #[test]
+  fn reports_matches_overlapping_edits() {
+    let mut s = MagicString::new("abcabc");
+    s.update(3, 5, "AB");
+    assert_eq!(
+      s.try_replace_all("bc", "_").unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 4, edited_start: 3, edited_end: 5 }
+    );
+    assert_eq!(s.to_string(), "a_ABc");
+  }
+
+  #[test]
+  fn inserts_at_empty_matches() {
+    let mut s = MagicString::new("abc");
+    s.replace("", "_");
+    assert_eq!(s.to_string(), "_abc");
+    let mut s = MagicString::new("abc");
+    s.replace_all("", "_");
+    assert_eq!(s.to_string(), "_a_b_c_");
+  }
+}
+
+mod reset {
+  use super::*;
+
+  #[test]
+  fn should_reset_removed_ranges() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.remove(1, 5);
+    assert_eq!(s.to_string(), "afghijkl");
+    s.reset(1, 5);
+    assert_eq!(s.to_string(), "abcdefghijkl");
+
+    s.remove(1, 5);
+    s.reset(2, 4);
+    assert_eq!(s.to_string(), "acdfghijkl");
+    s.reset(1, 2);
+    assert_eq!(s.to_string(), "abcdfghijkl");
+    s.reset(4, 5);
+    assert_eq!(s.to_string(), "abcdefghijkl");
+  }
+
+  #[test]
+  fn should_reset_modified_ranges() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.overwrite(3, 6, "DEF");
+    s.reset(3, 6);
+    assert_eq!(s.to_string(), "abcdefghijkl");
+
+    s.overwrite(3, 6, "DEF");
+    s.reset(2, 7);
+    assert_eq!(s.to_string(), "abcdefghijkl");
+  }
+
+  #[test]
+  fn rejects_resetting_a_part_of_a_replacement() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(2, 8, "X");
+    assert_eq!(
+      s.try_reset(5, 8).unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 5, edited_start: 2, edited_end: 8 }
+    );
+    // Also when the replacement was split by an insertion
+    s.append_left(5, "!");
+    assert_eq!(
+      s.try_reset(5, 8).unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 5, edited_start: 2, edited_end: 8 }
+    );
+    assert_eq!(s.to_string(), "abX!ijkl");
+    s.reset(2, 8);
+    assert_eq!(s.to_string(), "abcdefghijkl");
+  }
+
+  #[test]
+  fn should_clear_inserts_in_the_range_unless_told_otherwise() {
+    let mut s = MagicString::new("abc");
+    s.update(1, 2, "X").append_left(1, "A").prepend_right(1, "B").append_left(2, "C");
+    assert_eq!(s.to_string(), "aABXCc");
+    s.reset_with(1, 2, ResetOptions { overwrite: false });
+    assert_eq!(s.to_string(), "aABbCc");
+    // The outro of `a` is outside of the range
+    s.reset(1, 2);
+    assert_eq!(s.to_string(), "aAbc");
+  }
+
+  #[test]
+  fn should_keep_relocations() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.relocate(3, 6, 9).update(3, 6, "DEF");
+    assert_eq!(s.to_string(), "abcghiDEFjkl");
+    s.reset(3, 6);
+    assert_eq!(s.to_string(), "abcghidefjkl");
+  }
+
+  #[test]
+  fn should_reset_part_of_a_removed_range() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.remove(2, 10);
+    s.reset(4, 6);
+    assert_eq!(s.to_string(), "abefkl");
+    assert_eq!(s.try_reset(5, 3).err(), Some(MagicStringError::InvalidRange { start: 5, end: 3 }));
+  }
+}
+
+mod compact {
+  use super::*;
+
+  #[test]
+  fn keeps_the_output_and_later_edits_working() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(3, 6, "DEF").prepend_right(9, "?").relocate(0, 2, 12);
+    s.reset(3, 6).compact();
+    assert_eq!(s.to_string(), "cdefghi?jklab");
+    s.update(4, 8, "X").append_left(1, "!");
+    assert_eq!(s.to_string(), "cdXi?jkla!b");
+  }
+
+  #[test]
+  fn automatically_once_there_are_enough_chunks() {
+    let source = "let a = 1;\n".repeat(100);
+    let mut compacted = MagicString::with_options(
+      source.as_str(),
+      MagicStringOptions { compact_threshold: Some(8), ..Default::default() },
+    );
+    let mut s = MagicString::new(source.as_str());
+    for s in [&mut compacted, &mut s] {
+      for line in 0..100 {
+        let start = line * 11;
+        s.update(start, start + 3, "const").reset(start, start + 3);
+        if line % 10 == 0 {
+          s.append_left(start + 9, " + 1");
+        }
+      }
+    }
+    assert_eq!(compacted.to_string(), s.to_string());
+  }
+}
+
 mod indent {
   use string_wizard::IndentOptions;
 
@@ -287,6 +833,16 @@ mod indent {
     assert_eq!(s.to_string(), "\tabc\n\tdef\n\tghi\n\tjkl")
   }
 
+  #[test]
+  fn indents_each_line_once_with_a_compact_threshold() {
+    let mut s = MagicString::with_options(
+      "a\nb\nc\nd\ne\nf\ng\nh\n",
+      MagicStringOptions { compact_threshold: Some(6), ..Default::default() },
+    );
+    s.update(4, 5, "C").update(10, 11, "F").relocate(12, 16, 0).indent();
+    assert_eq!(s.to_string(), "\tg\n\th\n\ta\n\tb\n\tC\n\td\n\te\n\tF\n");
+  }
+
   #[test]
   fn should_indent_content_using_existing_indentation_as_a_guide() {
     let mut s = MagicString::new("abc\n  def\n    ghi\n  jkl");
@@ -375,6 +931,418 @@ mod indent {
   }
 }
 
+mod has_changed {
+  use super::*;
+
+  #[test]
+  fn should_report_edits() {
+    let mut s = MagicString::new("abc");
+    assert!(!s.has_changed());
+    // Nothing to trim
+    s.trim();
+    assert!(!s.has_changed());
+    s.update(1, 2, "B");
+    assert!(s.has_changed());
+    s.reset(1, 2);
+    assert_eq!(s.to_string(), "abc");
+    assert!(s.has_changed());
+
+    let mut s = MagicString::new("abc");
+    s.relocate(0, 1, 3);
+    assert!(s.has_changed());
+  }
+
+  #[test]
+  fn should_keep_the_original() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.remove(0, 3).prepend(">").relocate(6, 9, 3);
+    assert_eq!(s.to_string(), ">ghidefjkl");
+    assert_eq!(s.original(), "abcdefghijkl");
+  }
+
+  #[test]
+  fn is_empty() {
+    assert!(MagicString::new("").is_empty());
+    let mut s = MagicString::new("abc");
+    assert!(!s.is_empty());
+    s.remove(0, 3);
+    assert!(s.is_empty());
+    s.append_left(1, "");
+    assert!(s.is_empty());
+    s.append("x");
+    assert!(!s.is_empty());
+  }
+}
+
+mod original_position_for {
+  use super::*;
+
+  #[test]
+  fn should_locate_original_edited_and_inserted_content() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.prepend("^").append("$");
+    s.update(3, 6, "DEF").remove(6, 9).append_left(3, "(").prepend_right(9, ")");
+    s.relocate(0, 3, 12);
+    // The outro of `abc` moves along with it
+    assert_eq!(s.to_string(), "^DEF)jklabc($");
+
+    let positions = (0..=s.len()).map(|i| s.original_position_for(i).unwrap()).collect::<Vec<_>>();
+    let edited = OriginalPosition::Edited { original_span: Span(3, 6) };
+    assert_eq!(
+      positions,
+      vec![
+        OriginalPosition::Inserted { at: None },
+        edited,
+        edited,
+        edited,
+        OriginalPosition::Inserted { at: Some(9) },
+        OriginalPosition::Original(9),
+        OriginalPosition::Original(10),
+        OriginalPosition::Original(11),
+        OriginalPosition::Original(0),
+        OriginalPosition::Original(1),
+        OriginalPosition::Original(2),
+        OriginalPosition::Inserted { at: Some(3) },
+        OriginalPosition::Inserted { at: None },
+        // The end belongs to the last fragment
+        OriginalPosition::Inserted { at: None },
+      ]
+    );
+    assert_eq!(s.original_position_for(s.len() + 1), None);
+  }
+
+  #[test]
+  fn should_locate_the_end_of_the_content() {
+    let mut s = MagicString::new("abc");
+    assert_eq!(s.original_position_for(3), Some(OriginalPosition::Original(3)));
+    assert_eq!(s.original_position_for(4), None);
+    s.update(0, 1, "A");
+    assert_eq!(s.original_position_for(3), Some(OriginalPosition::Original(3)));
+    s.remove(1, 3);
+    assert_eq!(
+      s.original_position_for(1),
+      Some(OriginalPosition::Edited { original_span: Span(0, 1) })
+    );
+    s.remove(0, 1);
+    assert_eq!(s.original_position_for(0), None);
+  }
+
+  #[test]
+  fn should_report_the_whole_span_of_a_split_replacement() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(2, 8, "X").append_left(5, "!");
+    assert_eq!(s.to_string(), "abX!ijkl");
+    assert_eq!(
+      s.original_position_for(2),
+      Some(OriginalPosition::Edited { original_span: Span(2, 8) })
+    );
+    assert_eq!(s.original_position_for(3), Some(OriginalPosition::Inserted { at: Some(5) }));
+  }
+}
+
+mod generated_offset_for {
+  use super::*;
+
+  #[test]
+  fn should_follow_insertions_and_relocations() {
+    let mut s = MagicString::new("abcdefghijkl");
+    assert_eq!(s.generated_offset_for(5), Some(5));
+    s.prepend(">>").append_left(3, "(").prepend_right(3, "[").relocate(6, 9, 0);
+    assert_eq!(s.to_string(), ">>ghiabc([defjkl");
+    let offsets = (0..=12).map(|i| s.generated_offset_for(i)).collect::<Vec<_>>();
+    assert_eq!(
+      offsets,
+      [5, 6, 7, 10, 11, 12, 2, 3, 4, 13, 14, 15, 16].into_iter().map(Some).collect::<Vec<_>>()
+    );
+    assert_eq!(s.generated_offset_for(13), None);
+  }
+
+  #[test]
+  fn should_return_none_for_removed_text() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.remove(0, 3).update(6, 9, "GHI").remove(9, 12);
+    assert_eq!(s.to_string(), "defGHI");
+    assert_eq!(s.generated_offset_for(1), None);
+    assert_eq!(s.generated_offset_for(3), Some(0));
+    assert_eq!(s.generated_offset_for(8), Some(3));
+    assert_eq!(s.generated_offset_for(12), None);
+  }
+
+  #[test]
+  fn line_column() {
+    let mut s = MagicString::new("const ß = 1;\nlet 💣 = ß;");
+    assert_eq!(
+      s.generated_position_for(LineColumn { line: 1, column: 6 }),
+      Some(LineColumn { line: 1, column: 6 })
+    );
+    s.prepend("// 中文\n").update(0, 5, "var").remove(18, 23);
+    assert_eq!(s.to_string(), "// 中文\nvar ß = 1;\nlet = ß;");
+    assert_eq!(
+      s.generated_position_for(LineColumn { line: 0, column: 6 }),
+      Some(LineColumn { line: 1, column: 4 })
+    );
+    assert_eq!(
+      s.generated_position_for(LineColumn { line: 1, column: 9 }),
+      Some(LineColumn { line: 2, column: 6 })
+    );
+    // Removed, in the middle of `💣` and out of bounds
+    assert_eq!(s.generated_position_for(LineColumn { line: 1, column: 4 }), None);
+    assert_eq!(s.generated_position_for(LineColumn { line: 1, column: 5 }), None);
+    assert_eq!(s.generated_position_for(LineColumn { line: 0, column: 13 }), None);
+    assert_eq!(s.generated_position_for(LineColumn { line: 2, column: 0 }), None);
+  }
+}
+
+mod marker {
+  use super::*;
+
+  #[test]
+  fn sticks_to_its_side_of_insertions() {
+    let mut s = MagicString::new("abcdef");
+    let left = s.mark(3, Bias::Left);
+    let right = s.mark(3, Bias::Right);
+    s.append_left(3, "<").prepend_right(3, ">");
+    assert_eq!(s.to_string(), "abc<>def");
+    assert_eq!(s.marker_generated_offset(left), Some(3));
+    assert_eq!(s.marker_generated_offset(right), Some(5));
+  }
+
+  #[test]
+  fn follows_split_and_relocated_chunks() {
+    let mut s = MagicString::new("abcdefghijkl");
+    let marker = s.mark(5, Bias::Right);
+    s.append_left(4, "!").relocate(3, 9, 12).prepend("^");
+    assert_eq!(s.to_string(), "^abcjkld!efghi");
+    assert_eq!(s.marker_generated_offset(marker), Some(10));
+    s.compact();
+    assert_eq!(s.marker_generated_offset(marker), Some(10));
+  }
+
+  #[test]
+  fn removed_or_replaced_anchors() {
+    let mut s = MagicString::new("abcdefghijkl");
+    let removed = s.mark(6, Bias::Left);
+    let after_removed = s.mark(6, Bias::Right);
+    let replaced_left = s.mark(10, Bias::Left);
+    let replaced_right = s.mark(10, Bias::Right);
+    s.remove(3, 6).update(9, 12, "XYZ!");
+    assert_eq!(s.to_string(), "abcghiXYZ!");
+    assert_eq!(s.marker_generated_offset(removed), None);
+    assert_eq!(s.marker_generated_offset(after_removed), Some(3));
+    assert_eq!(s.marker_generated_offset(replaced_left), Some(10));
+    assert_eq!(s.marker_generated_offset(replaced_right), Some(6));
+  }
+
+  #[test]
+  fn ends_of_the_source() {
+    let mut s = MagicString::new("abc");
+    let start = s.mark(0, Bias::Left);
+    let end = s.mark(3, Bias::Right);
+    s.prepend("<").append(">");
+    assert_eq!(s.marker_generated_offset(start), Some(0));
+    assert_eq!(s.marker_generated_offset(end), Some(5));
+    assert!(matches!(s.try_mark(4, Bias::Left), Err(MagicStringError::OutOfBounds { .. })));
+  }
+}
+
+mod segments {
+  use super::*;
+
+  #[test]
+  fn report_where_each_piece_comes_from() {
+    let mut s = MagicString::new("abcdefghi");
+    s.remove(0, 2)
+      .update_with(3, 6, "DEF", UpdateOptions { keep_original: true, ..Default::default() })
+      .append_left(6, ";")
+      .relocate(6, 9, 0)
+      .append("$");
+    assert_eq!(s.to_string(), "ghicDEF;$");
+    assert_eq!(
+      s.segments().collect::<Vec<_>>(),
+      [
+        Segment { text: "ghi", kind: SegmentKind::Original { span: Span(6, 9) } },
+        Segment {
+          text: "",
+          kind: SegmentKind::Edited { original_span: Span(0, 2), name_kept: false }
+        },
+        Segment { text: "c", kind: SegmentKind::Original { span: Span(2, 3) } },
+        Segment {
+          text: "DEF",
+          kind: SegmentKind::Edited { original_span: Span(3, 6), name_kept: true }
+        },
+        Segment { text: ";", kind: SegmentKind::ChunkOutro { at: 6 } },
+        Segment { text: "$", kind: SegmentKind::GlobalOutro },
+      ]
+    );
+  }
+
+  #[test]
+  fn report_a_split_replacement_once() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(2, 8, "X").append_left(5, "!").remove(8, 10);
+    assert_eq!(
+      s.segments().collect::<Vec<_>>(),
+      [
+        Segment { text: "ab", kind: SegmentKind::Original { span: Span(0, 2) } },
+        Segment {
+          text: "X",
+          kind: SegmentKind::Edited { original_span: Span(2, 8), name_kept: false }
+        },
+        Segment { text: "!", kind: SegmentKind::ChunkOutro { at: 5 } },
+        Segment {
+          text: "",
+          kind: SegmentKind::Edited { original_span: Span(8, 10), name_kept: false }
+        },
+        Segment { text: "kl", kind: SegmentKind::Original { span: Span(10, 12) } },
+      ]
+    );
+  }
+
+  #[test]
+  fn concatenate_to_the_generated_content() {
+    let mut s = MagicString::new("let a = 1;\nlet b = 2;");
+    s.prepend("/* head */\n").prepend_right(11, "\t").update(15, 16, "c").indent();
+    let text: String = s.segments().map(|segment| segment.text).collect();
+    assert_eq!(text, s.to_string());
+  }
+}
+
+mod edits {
+  use super::*;
+
+  fn assert_round_trip(s: &MagicString) {
+    let replayed = MagicString::from_edits(s.original(), s.edits());
+    assert_eq!(replayed.to_string(), s.to_string());
+    assert_eq!(replayed.edits(), s.edits());
+  }
+
+  #[test]
+  fn untouched() {
+    let s = MagicString::new("abc");
+    assert_eq!(s.edits(), []);
+    assert!(!MagicString::from_edits("abc", s.edits()).has_changed());
+  }
+
+  #[test]
+  fn merge_the_pieces_of_split_edits() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(0, 6, "X").remove(6, 9).append_left(3, "!").append_left(7, "?").remove(7, 8);
+    assert_eq!(s.to_string(), "X!?jkl");
+    assert_eq!(
+      s.edits(),
+      [
+        Edit::Replace { start: 0, end: 6, content: "X".to_string(), keep_original: false },
+        Edit::Remove { start: 6, end: 9 },
+        Edit::Insert { at: 3, bias: Bias::Left, content: "!".to_string() },
+        Edit::Insert { at: 7, bias: Bias::Left, content: "?".to_string() },
+      ]
+    );
+    assert_round_trip(&s);
+  }
+
+  #[test]
+  fn inserts_keep_their_side_and_order() {
+    let mut s = MagicString::new("abcdef");
+    s.prepend("1")
+      .append_left(0, "2")
+      .prepend_right(0, "3")
+      .append_right(3, "4")
+      .prepend_left(3, "5")
+      .append_left(3, "6")
+      .append_left(6, "7")
+      .append("8")
+      .relocate(0, 2, 6);
+    assert_eq!(s.to_string(), "12c564def73ab8");
+    assert_round_trip(&s);
+  }
+
+  #[test]
+  fn relocations() {
+    let source = "0123456789";
+    let cases: &[&[(usize, usize, usize)]] = &[
+      &[(0, 3, 10)],
+      &[(7, 10, 0)],
+      &[(2, 4, 8), (6, 7, 1)],
+      &[(0, 2, 10), (2, 4, 10), (4, 6, 10)],
+      &[(8, 10, 0), (6, 8, 0), (4, 6, 0), (2, 4, 0)],
+      &[(1, 2, 9), (3, 4, 7), (5, 6, 3), (7, 8, 0)],
+    ];
+    for relocations in cases {
+      let mut s = MagicString::new(source);
+      s.update(4, 5, "four").append_left(6, "!");
+      for &(start, end, to) in *relocations {
+        s.relocate(start, end, to);
+      }
+      assert_round_trip(&s);
+      let relocation_count =
+        s.edits().iter().filter(|edit| matches!(edit, Edit::Relocate { .. })).count();
+      assert!(relocation_count <= relocations.len());
+    }
+  }
+
+  #[test]
+  fn invalid_edits() {
+    let edits = [Edit::Remove { start: 2, end: 4 }];
+    assert!(matches!(
+      MagicString::try_from_edits("abc", edits),
+      Err(MagicStringError::OutOfBounds { index: 4, len: 3 })
+    ));
+  }
+}
+
+mod write {
+  use super::*;
+
+  #[test]
+  fn should_stream_the_generated_content() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.prepend("^").update(3, 6, "DEF").remove(6, 9).prepend_right(9, ">").relocate(0, 3, 12);
+    let expected = "^DEF>jklabc";
+
+    let mut bytes = vec![];
+    s.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes, expected.as_bytes());
+
+    let mut string = String::from("// ");
+    s.write_fmt_to(&mut string).unwrap();
+    assert_eq!(string, format!("// {expected}"));
+
+    assert_eq!(format!("{s}"), expected);
+    assert_eq!(s.to_string(), expected);
+  }
+}
+
+mod hash_into {
+  use std::hash::{DefaultHasher, Hasher};
+
+  use super::*;
+
+  fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(bytes);
+    hasher.finish()
+  }
+
+  #[test]
+  fn should_equal_hashing_the_rendered_content() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.prepend("^").update(3, 6, "DEF").remove(6, 9).append_left(9, "").relocate(0, 3, 12);
+    let mut hasher = DefaultHasher::new();
+    s.hash_into(&mut hasher);
+    assert_eq!(hasher.finish(), hash_bytes(s.to_string().as_bytes()));
+
+    let mut other = MagicString::new("^DEFjklabc");
+    let mut other_hasher = DefaultHasher::new();
+    other.hash_into(&mut other_hasher);
+    assert_eq!(other_hasher.finish(), hash_bytes(s.to_string().as_bytes()));
+
+    other.update(0, 1, "$");
+    let mut other_hasher = DefaultHasher::new();
+    other.hash_into(&mut other_hasher);
+    assert_ne!(other_hasher.finish(), hash_bytes(s.to_string().as_bytes()));
+  }
+}
+
 mod misc {
   use super::*;
 
@@ -405,3 +1373,78 @@ mod misc {
     assert_eq!(s.to_string(), "xyzxyz");
   }
 }
+
+mod try_api {
+  use super::*;
+
+  #[test]
+  fn reports_out_of_bounds_indices() {
+    let mut s = MagicString::new("abc");
+    assert_eq!(
+      s.try_append_left(4, "x").unwrap_err(),
+      MagicStringError::OutOfBounds { index: 4, len: 3 }
+    );
+    assert_eq!(
+      s.try_update(1, 5, "x").unwrap_err(),
+      MagicStringError::OutOfBounds { index: 5, len: 3 }
+    );
+    assert_eq!(s.to_string(), "abc");
+  }
+
+  #[test]
+  fn reports_non_char_boundary_indices() {
+    let mut s = MagicString::new("aßc");
+    assert_eq!(
+      s.try_prepend_right(2, "x").unwrap_err(),
+      MagicStringError::NotCharBoundary { index: 2 }
+    );
+    assert_eq!(s.try_remove(0, 2).unwrap_err(), MagicStringError::NotCharBoundary { index: 2 });
+    s.try_remove(1, 3).unwrap();
+    assert_eq!(s.to_string(), "ac");
+  }
+
+  #[test]
+  fn reports_invalid_and_zero_length_ranges() {
+    let mut s = MagicString::new("abcdef");
+    assert_eq!(
+      s.try_update(4, 2, "x").unwrap_err(),
+      MagicStringError::InvalidRange { start: 4, end: 2 }
+    );
+    assert_eq!(
+      s.try_update(2, 2, "x").unwrap_err(),
+      MagicStringError::ZeroLengthRange { index: 2 }
+    );
+    // Removing an empty range is a no-op
+    s.try_remove(2, 2).unwrap();
+    assert_eq!(s.to_string(), "abcdef");
+  }
+
+  #[test]
+  fn reports_overlapping_edits() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.try_update(7, 11, "xx").unwrap();
+    assert_eq!(
+      s.try_update(8, 12, "y").unwrap_err(),
+      MagicStringError::OverlappingEdit { index: 8, edited_start: 7, edited_end: 11 }
+    );
+    assert_eq!(s.to_string(), "abcdefgxxl");
+  }
+
+  #[test]
+  fn reports_moves_into_self() {
+    let mut s = MagicString::new("abcdefghijkl");
+    assert_eq!(
+      s.try_relocate(3, 6, 4).unwrap_err(),
+      MagicStringError::MoveIntoSelf { start: 3, end: 6, to: 4 }
+    );
+    s.try_relocate(3, 6, 9).unwrap().try_append_left(9, "X").unwrap();
+    assert_eq!(s.to_string(), "abcghiXdefjkl");
+  }
+
+  #[test]
+  #[should_panic(expected = "Cannot split a chunk that has already been edited")]
+  fn panicking_methods_report_the_error() {
+    let mut s = MagicString::new("abcdefghijkl");
+    s.update(7, 11, "xx").update(8, 12, "yy");
+  }
+}
@@ -0,0 +1,50 @@
+use regex::{Captures, NoExpand, Regex};
+use string_wizard::{MagicString, MagicStringError};
+
+#[test]
+fn replaces_the_first_match() {
+  let re = Regex::new(r"(\w+)\.(\w+)").unwrap();
+  let mut s = MagicString::new("a.b; c.d;");
+  s.replace_regex(&re, "$2[$1]");
+  assert_eq!(s.to_string(), "b[a]; c.d;");
+}
+
+#[test]
+fn replaces_all_matches() {
+  let re = Regex::new(r"(?<obj>\w+)\.(?<prop>\w+)").unwrap();
+  let mut s = MagicString::new("a.b; c.d;");
+  s.replace_all_regex(&re, "${obj}['${prop}']");
+  assert_eq!(s.to_string(), "a['b']; c['d'];");
+}
+
+#[test]
+fn supports_closures_and_no_expand() {
+  let re = Regex::new(r"\d+").unwrap();
+  let mut s = MagicString::new("1 + 22 + 333");
+  s.replace_all_regex(&re, |caps: &Captures| (caps[0].parse::<u32>().unwrap() * 2).to_string());
+  assert_eq!(s.to_string(), "2 + 44 + 666");
+
+  let mut s = MagicString::new("1 + 22");
+  s.replace_regex(&Regex::new(r"\+").unwrap(), NoExpand("$0"));
+  assert_eq!(s.to_string(), "1 $0 22");
+}
+
+#[test]
+fn reports_matches_overlapping_edits() {
+  let re = Regex::new(r"foo").unwrap();
+  let mut s = MagicString::new("foo; foo;");
+  s.update(6, 9, "bar;");
+  assert_eq!(
+    s.try_replace_all_regex(&re, "baz").unwrap_err(),
+    MagicStringError::OverlappingEdit { index: 8, edited_start: 6, edited_end: 9 }
+  );
+  assert_eq!(s.to_string(), "baz; fbar;");
+}
+
+#[test]
+fn inserts_at_empty_matches() {
+  let re = Regex::new(r"\b").unwrap();
+  let mut s = MagicString::new("a.b");
+  s.replace_all_regex(&re, "|");
+  assert_eq!(s.to_string(), "|a|.|b|");
+}
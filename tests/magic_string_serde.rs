@@ -0,0 +1,14 @@
+use string_wizard::{Edit, MagicString, UpdateOptions};
+
+#[test]
+fn edits_round_trip_through_json() {
+  let mut s = MagicString::new("let a = 1;\nlet b = a;\n");
+  s.prepend("'use strict';\n")
+    .update_with(15, 16, "c", UpdateOptions { keep_original: true, ..Default::default() })
+    .remove(4, 5)
+    .relocate(0, 11, 22);
+  let json = serde_json::to_string(&s.edits()).unwrap();
+  let edits: Vec<Edit> = serde_json::from_str(&json).unwrap();
+  assert_eq!(edits, s.edits());
+  assert_eq!(MagicString::from_edits(s.original(), edits).to_string(), s.to_string());
+}
@@ -1,4 +1,4 @@
-use string_wizard::{MagicString, SourceMapOptions, UpdateOptions};
+use string_wizard::{HashPlaceholders, Hires, MagicString, SourceMapOptions, UpdateOptions};
 
 #[test]
 fn basic() {
@@ -23,10 +23,302 @@ fn basic() {
         "{\"version\":3,\"names\":[\"d\",\"v\",\"div\"],\"sources\":[\"\"],\"sourcesContent\":[\"<div>\\n  hello, world\\n</div>\"],\"mappings\":\";AAAA,CAACA,CAAC,CAACC,CAAC;AACJ;AACA,EAAEC,EAAG\"}"
     );
 
-  let sm =
-    s.source_map(SourceMapOptions { include_content: true, hires: true, ..Default::default() });
+  let sm = s.source_map(SourceMapOptions {
+    include_content: true,
+    hires: Hires::Char,
+    ..Default::default()
+  });
   assert_eq!(
         sm.to_json_string(),
         "{\"version\":3,\"names\":[\"d\",\"v\",\"div\"],\"sources\":[\"\"],\"sourcesContent\":[\"<div>\\n  hello, world\\n</div>\"],\"mappings\":\";AAAA,CAACA,CAAC,CAACC,CAAC,CAAC;AACL,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC,CAAC;AACd,CAAC,CAACC,EAAG\"}"
     );
 }
+
+#[test]
+fn multi_byte_chars_before_edits() {
+  // `ß` is 2 bytes/1 utf-16 unit, `💣` is 4 bytes/2 utf-16 units, `中` is 3 bytes/1 utf-16 unit.
+  let input = "const ß = '💣';\nlet 中 = ß;";
+  let mut s = MagicString::new(input);
+  let update_options = UpdateOptions { keep_original: true, ..Default::default() };
+  let second_sharp_s = input.rfind('ß').unwrap();
+  s.update_with(6, 8, "ss", update_options.clone())
+    .update_with(12, 16, "bomb", update_options.clone())
+    .update_with(second_sharp_s, second_sharp_s + 2, "ss", update_options.clone());
+  assert_eq!(s.to_string(), "const ss = 'bomb';\nlet 中 = ss;");
+
+  let sm = s.source_map(SourceMapOptions::default());
+  let tokens = sm
+    .get_tokens()
+    .map(|token| {
+      let name = token.get_name_id().and_then(|id| sm.get_name(id));
+      (token.get_dst_line(), token.get_dst_col(), token.get_src_line(), token.get_src_col(), name)
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(
+    tokens,
+    vec![
+      (0, 0, 0, 0, None),
+      (0, 6, 0, 6, Some("ß")),
+      (0, 8, 0, 7, None),
+      (0, 12, 0, 11, Some("💣")),
+      (0, 16, 0, 13, None),
+      (1, 0, 1, 0, None),
+      (1, 8, 1, 8, Some("ß")),
+      (1, 10, 1, 9, None),
+    ]
+  );
+}
+
+#[test]
+fn snip_keeps_original_positions() {
+  let input = "const a = 1;\nconst b = 2;";
+  let mut s = MagicString::new(input);
+  s.update_with(19, 20, "c", UpdateOptions { keep_original: true, ..Default::default() });
+  let snipped = s.snip(13, 25);
+  assert_eq!(snipped.to_string(), "const c = 2;");
+
+  let sm = snipped.source_map(SourceMapOptions::default());
+  let tokens = sm
+    .get_tokens()
+    .map(|token| {
+      (token.get_dst_line(), token.get_dst_col(), token.get_src_line(), token.get_src_col())
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(tokens, vec![(0, 0, 1, 0), (0, 6, 1, 6), (0, 7, 1, 7)]);
+}
+
+#[test]
+fn trim_keeps_mappings() {
+  let mut s = MagicString::new("  \n  abc  \n");
+  s.trim();
+  assert_eq!(s.to_string(), "abc");
+  let sm = s.source_map(SourceMapOptions::default());
+  let tokens = sm
+    .get_tokens()
+    .map(|token| {
+      (token.get_dst_line(), token.get_dst_col(), token.get_src_line(), token.get_src_col())
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(tokens, vec![(0, 0, 1, 2)]);
+}
+
+#[test]
+fn untouched_strings_map_to_themselves() {
+  let input = "const ß = 1;\n\nlet b = ß;\n";
+  let untouched = MagicString::new(input);
+  // An edit without visible effect takes the regular path
+  let mut edited = MagicString::new(input);
+  edited.append("");
+  assert!(!untouched.has_changed() && edited.has_changed());
+
+  for hires in [Hires::Off, Hires::Boundary, Hires::Char] {
+    let untouched_sm = untouched.source_map(SourceMapOptions { hires, ..Default::default() });
+    let edited_sm = edited.source_map(SourceMapOptions { hires, ..Default::default() });
+    assert_eq!(untouched_sm.to_json_string(), edited_sm.to_json_string());
+  }
+  assert_eq!(
+    untouched
+      .source_map(SourceMapOptions { include_content: true, ..Default::default() })
+      .to_json_string(),
+    "{\"version\":3,\"names\":[],\"sources\":[\"\"],\"sourcesContent\":[\"const ß = 1;\\n\\nlet b = ß;\\n\"],\"mappings\":\"AAAA;AACA;AACA\"}"
+  );
+}
+
+#[test]
+fn compact_drops_mappings_of_merged_chunks() {
+  let input = "const a = 1;\nlet b = a;\n";
+  let untouched = MagicString::new(input);
+  let mut s = MagicString::new(input);
+  s.update(6, 7, "x").update(21, 22, "x").reset(0, input.len());
+  assert_ne!(
+    s.source_map(SourceMapOptions::default()).to_json_string(),
+    untouched.source_map(SourceMapOptions::default()).to_json_string()
+  );
+  s.compact();
+  assert_eq!(
+    s.source_map(SourceMapOptions::default()).to_json_string(),
+    untouched.source_map(SourceMapOptions::default()).to_json_string()
+  );
+}
+
+#[test]
+fn replayed_edits_map_every_char_the_same() {
+  let input = "const a = 1;\nlet b = a;\n";
+  let mut s = MagicString::new(input);
+  s.update_with(6, 7, "c", UpdateOptions { keep_original: true, ..Default::default() })
+    .remove(13, 17)
+    .append_left(12, " // a")
+    .relocate(13, 23, 0);
+  let replayed = MagicString::from_edits(input, s.edits());
+  let opts = || SourceMapOptions { hires: Hires::Char, ..Default::default() };
+  assert_eq!(replayed.source_map(opts()).to_json_string(), s.source_map(opts()).to_json_string());
+}
+
+#[test]
+fn include_content_file_and_source_root() {
+  let mut s = MagicString::new("let a = 1;");
+  s.update(4, 5, "b");
+
+  let sm = s.source_map(SourceMapOptions { source: "a.js".into(), ..Default::default() });
+  assert_eq!(
+    sm.to_json_string(),
+    "{\"version\":3,\"names\":[],\"sources\":[\"a.js\"],\"mappings\":\"AAAA,IAAI,CAAC\"}"
+  );
+
+  let sm = s.source_map(SourceMapOptions {
+    source: "a.js".into(),
+    include_content: true,
+    file: Some("a.min.js".to_string()),
+    ..Default::default()
+  });
+  assert_eq!(
+    sm.to_json_string(),
+    "{\"version\":3,\"file\":\"a.min.js\",\"names\":[],\"sources\":[\"a.js\"],\"sourcesContent\":[\"let a = 1;\"],\"mappings\":\"AAAA,IAAI,CAAC\"}"
+  );
+
+  let sm = s.source_map(SourceMapOptions {
+    source: "a.js".into(),
+    include_content: true,
+    file: Some("a.min.js".to_string()),
+    source_root: Some("src/".to_string()),
+    ..Default::default()
+  });
+  assert_eq!(
+    sm.to_json_string(),
+    "{\"version\":3,\"file\":\"a.min.js\",\"sourceRoot\":\"src/\",\"names\":[],\"sources\":[\"a.js\"],\"sourcesContent\":[\"let a = 1;\"],\"mappings\":\"AAAA,IAAI,CAAC\"}"
+  );
+}
+
+#[test]
+fn hires_boundary() {
+  let mut s = MagicString::new("foo.bar(a_1, 'ß');\nbaz");
+  s.update(0, 3, "qux");
+  let sm = s.source_map(SourceMapOptions { hires: Hires::Boundary, ..Default::default() });
+  let tokens = sm
+    .get_tokens()
+    .map(|token| {
+      (token.get_dst_line(), token.get_dst_col(), token.get_src_line(), token.get_src_col())
+    })
+    .collect::<Vec<_>>();
+  assert_eq!(
+    tokens,
+    vec![
+      // `qux`
+      (0, 0, 0, 0),
+      // `.`, `bar`, `(`, `a_1`, `,`, ` `, `'`, `ß`, `'`, `)`, `;`, but not `\n`
+      (0, 3, 0, 3),
+      (0, 4, 0, 4),
+      (0, 7, 0, 7),
+      (0, 8, 0, 8),
+      (0, 11, 0, 11),
+      (0, 12, 0, 12),
+      (0, 13, 0, 13),
+      (0, 14, 0, 14),
+      (0, 15, 0, 15),
+      (0, 16, 0, 16),
+      (0, 17, 0, 17),
+      // `baz`
+      (1, 0, 1, 0),
+    ]
+  );
+}
+
+#[test]
+fn chained_through_an_input_map() {
+  let original = "let a: number = 1;\nlog(a);";
+  let keep_original = UpdateOptions { keep_original: true, ..Default::default() };
+  let mut first = MagicString::new(original);
+  first.update_with(4, 5, "x", keep_original.clone()).remove(5, 13).update_with(
+    23,
+    24,
+    "x",
+    keep_original.clone(),
+  );
+  let intermediate = first.to_string();
+  assert_eq!(intermediate, "let x = 1;\nlog(x);");
+  let input_map = first.source_map(SourceMapOptions {
+    source: "a.ts".into(),
+    include_content: true,
+    hires: Hires::Char,
+    ..Default::default()
+  });
+
+  let mut second = MagicString::new(intermediate.as_str());
+  second.update_with(4, 5, "b", keep_original.clone()).update_with(15, 16, "b", keep_original);
+  assert_eq!(second.to_string(), "let b = 1;\nlog(b);");
+  let sm = second.source_map_chained(
+    SourceMapOptions {
+      include_content: true,
+      file: Some("a.js".to_string()),
+      ..Default::default()
+    },
+    &input_map,
+  );
+
+  assert_eq!(sm.get_file(), Some("a.js"));
+  assert_eq!(sm.get_sources().collect::<Vec<_>>(), ["a.ts"]);
+  assert_eq!(sm.get_source_content(0), Some(original));
+  let tokens = sm
+    .get_tokens()
+    .map(|token| {
+      let name = token.get_name_id().and_then(|id| sm.get_name(id));
+      (token.get_dst_line(), token.get_dst_col(), token.get_src_line(), token.get_src_col(), name)
+    })
+    .collect::<Vec<_>>();
+  // The names come from the input map, since the first pass renamed `a` to `x`
+  assert_eq!(
+    tokens,
+    vec![
+      (0, 0, 0, 0, None),
+      (0, 4, 0, 4, Some("a")),
+      (0, 5, 0, 13, None),
+      (1, 0, 1, 0, None),
+      (1, 4, 1, 4, Some("a")),
+      (1, 5, 1, 5, None),
+    ]
+  );
+}
+
+#[test]
+fn names_of_split_replacements() {
+  let mut s = MagicString::new("let foo = 1;");
+  s.update_with(4, 7, "x", UpdateOptions { keep_original: true, ..Default::default() })
+    .append_left(5, "!");
+  let sm = s.source_map(SourceMapOptions::default());
+  // The name is added once, for the whole replaced text
+  assert_eq!(sm.get_names().collect::<Vec<_>>(), ["foo"]);
+}
+
+#[test]
+fn substitute_placeholders() {
+  let input = "import a from './a.js';\nimport b from './b.js';\nconsole.log(a, b);";
+  let mut placeholders = HashPlaceholders::new();
+  let a = placeholders.create();
+  let b = placeholders.create();
+  let render = |a: &str, b: &str| {
+    let mut s = MagicString::new(input);
+    s.update(16, 20, format!("a-{a}")).update(40, 44, format!("b-{b}")).append_left(64, b);
+    let sm = s.source_map(SourceMapOptions { hires: Hires::Boundary, ..Default::default() });
+    (s.to_string(), sm)
+  };
+
+  let (content, sm) = render(&a, &b);
+  placeholders.resolve(&a, "5f3a");
+  placeholders.resolve(&b, "ü_0123456789");
+  let (substituted, substituted_sm) = placeholders.substitute_with_source_map(&content, &sm);
+
+  let (expected, expected_sm) = render("5f3a", "ü_0123456789");
+  assert_eq!(substituted, expected);
+  assert_eq!(substituted_sm.to_json_string(), expected_sm.to_json_string());
+}
+
+#[test]
+#[should_panic(expected = "Placeholder values must not contain line breaks")]
+fn substitute_placeholders_with_line_breaks() {
+  let mut placeholders = HashPlaceholders::new();
+  let a = placeholders.create();
+  let s = MagicString::new(a.as_str());
+  let sm = s.source_map(SourceMapOptions::default());
+  placeholders.resolve(&a, "a\nb");
+  placeholders.substitute_with_source_map(&a, &sm);
+}